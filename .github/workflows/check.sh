@@ -3,4 +3,15 @@
 set -eux -o pipefail
 
 cargo install cargo-llvm-cov --locked
+cargo install cbindgen --locked
+
+# the committed C header must match src/ffi.rs
+cbindgen --config cbindgen.toml --output include/rs_fsrs.h
+git diff --exit-code include/rs_fsrs.h
+
+cargo clippy --all-targets --all-features -- -D warnings
+
 SKIP_TRAINING=1 cargo llvm-cov --release
+
+# tests behind optional features
+cargo test --features ffi,serde,rand_core,cli,server,rayon
@@ -0,0 +1,178 @@
+#ifndef RS_FSRS_H
+#define RS_FSRS_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define FSRS_ABI_VERSION 2
+
+typedef enum FsrsStatus {
+  FSRS_STATUS_OK = 0,
+  FSRS_STATUS_NULL_POINTER = 1,
+  FSRS_STATUS_INVALID_RATING = 2,
+  FSRS_STATUS_INVALID_STATE = 3,
+  FSRS_STATUS_INVALID_TIMESTAMP = 4,
+  FSRS_STATUS_PANIC = 5,
+  FSRS_STATUS_INVALID_QUEUE_STATUS = 6,
+} FsrsStatus;
+
+typedef struct FSRS FSRS;
+
+typedef struct FsrsParameters {
+  double request_retention;
+  int32_t maximum_interval;
+  double w[19];
+  bool enable_short_term;
+  bool enable_fuzz;
+} FsrsParameters;
+
+typedef struct FsrsCard {
+  /**
+   * Whether the card has an `id`
+   */
+  bool has_id;
+  int64_t id;
+  /**
+   * Whether the card has a `preset_id`, the default preset is used otherwise
+   */
+  bool has_preset_id;
+  int64_t preset_id;
+  int64_t due;
+  double stability;
+  double difficulty;
+  int64_t elapsed_days;
+  int64_t scheduled_days;
+  int32_t reps;
+  int32_t lapses;
+  int32_t state;
+  int64_t last_review;
+  int32_t status;
+  /**
+   * End of the burial when `status` is 2, ignored otherwise
+   */
+  int64_t buried_until;
+} FsrsCard;
+
+typedef struct FsrsReviewLog {
+  bool has_card_id;
+  int64_t card_id;
+  bool has_preset_id;
+  int64_t preset_id;
+  int32_t rating;
+  int64_t elapsed_days;
+  int64_t scheduled_days;
+  int32_t state;
+  int64_t reviewed_date;
+  /**
+   * Milliseconds taken to answer, -1 if not recorded
+   */
+  int64_t review_duration;
+  /**
+   * 0 (early), 1 (on time) or 2 (late)
+   */
+  int32_t timing;
+} FsrsReviewLog;
+
+typedef struct FsrsSchedulingInfo {
+  struct FsrsCard card;
+  struct FsrsReviewLog review_log;
+} FsrsSchedulingInfo;
+
+typedef struct FsrsRecordLog {
+  struct FsrsSchedulingInfo again;
+  struct FsrsSchedulingInfo hard;
+  struct FsrsSchedulingInfo good;
+  struct FsrsSchedulingInfo easy;
+} FsrsRecordLog;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Layout version of the structs in this library, `FSRS_ABI_VERSION` from its header.
+ */
+uint32_t fsrs_abi_version(void);
+
+/**
+ * Writes the default parameters to `out`.
+ *
+ * # Safety
+ *
+ * `out` must be null or point to writable memory for an `FsrsParameters`.
+ */
+enum FsrsStatus fsrs_default_parameters(struct FsrsParameters *out);
+
+/**
+ * Creates a scheduler, or returns null if `parameters` is null. Release it with `fsrs_free`.
+ *
+ * # Safety
+ *
+ * `parameters` must be null or point to a valid `FsrsParameters`.
+ */
+struct FSRS *fsrs_new(const struct FsrsParameters *parameters);
+
+/**
+ * Releases a scheduler created by `fsrs_new`.
+ *
+ * # Safety
+ *
+ * `fsrs` must be null or a pointer returned by `fsrs_new` that was not freed yet.
+ */
+void fsrs_free(struct FSRS *fsrs);
+
+/**
+ * Writes a new card due at `now` to `out`.
+ *
+ * # Safety
+ *
+ * `out` must be null or point to writable memory for an `FsrsCard`.
+ */
+enum FsrsStatus fsrs_card_new(int64_t now, struct FsrsCard *out);
+
+/**
+ * Previews the outcome of every rating for `card` reviewed at `now`.
+ *
+ * # Safety
+ *
+ * `fsrs` must come from `fsrs_new`, `card` must point to a valid `FsrsCard` and
+ * `out` to writable memory for an `FsrsRecordLog`; any of them may be null.
+ */
+enum FsrsStatus fsrs_repeat(const struct FSRS *fsrs,
+                            const struct FsrsCard *card,
+                            int64_t now,
+                            struct FsrsRecordLog *out);
+
+/**
+ * Reviews `card` at `now` with `rating` (1 = Again ... 4 = Easy).
+ *
+ * # Safety
+ *
+ * `fsrs` must come from `fsrs_new`, `card` must point to a valid `FsrsCard` and
+ * `out` to writable memory for an `FsrsSchedulingInfo`; any of them may be null.
+ */
+enum FsrsStatus fsrs_next(const struct FSRS *fsrs,
+                          const struct FsrsCard *card,
+                          int64_t now,
+                          int32_t rating,
+                          struct FsrsSchedulingInfo *out);
+
+/**
+ * Writes the probability of recalling `card` at `now` to `out`.
+ *
+ * # Safety
+ *
+ * `card` must point to a valid `FsrsCard` and `out` to a writable `double`; either may be null.
+ */
+enum FsrsStatus fsrs_card_get_retrievability(const struct FsrsCard *card, int64_t now, double *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RS_FSRS_H */
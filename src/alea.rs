@@ -1,6 +1,13 @@
+use core::fmt::{self, Display, Write};
+
 use crate::Seed;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AleaState {
     pub c: f64,
     pub s0: f64,
@@ -28,14 +35,13 @@ pub struct Alea {
 }
 
 impl Alea {
-    fn new(seed: Seed) -> Self {
+    fn new(seed: &impl Display) -> Self {
         let mut mash = Mash::new();
-        let blank_seed = Seed::new(" ");
         let mut alea = Self {
             c: 1.0,
-            s0: mash.mash(&blank_seed),
-            s1: mash.mash(&blank_seed),
-            s2: mash.mash(&blank_seed),
+            s0: mash.mash(&" "),
+            s1: mash.mash(&" "),
+            s2: mash.mash(&" "),
         };
 
         alea.s0 -= mash.mash(&seed);
@@ -95,9 +101,18 @@ impl Mash {
         Self { n: Self::N as f64 }
     }
 
-    fn mash(&mut self, seed: &Seed) -> f64 {
+    /// Mashes the formatted seed without collecting it into a string.
+    fn mash(&mut self, seed: &impl Display) -> f64 {
+        // Writing to `Mash` never fails.
+        let _ = write!(self, "{seed}");
+        self.n * TWO_TO_THE_POWER_OF_MINUS_32 // 2^-32
+    }
+}
+
+impl Write for Mash {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
         let mut n: f64 = self.n;
-        for c in seed.inner_str().chars() {
+        for c in s.chars() {
             n += c as u32 as f64;
             let mut h = 0.02519603282416938 * n;
             n = (h as u32) as f64;
@@ -108,33 +123,46 @@ impl Mash {
             n += h * TWO_TO_THE_POWER_OF_32 as f64;
         }
         self.n = n;
-        self.n * TWO_TO_THE_POWER_OF_MINUS_32 // 2^-32
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Prng {
     pub xg: Alea,
 }
 
 impl Prng {
-    fn new(seed: Seed) -> Self {
+    /// Seeds like `alea(String(seed))` in JavaScript. Unlike [`alea`], an empty
+    /// seed is used as is.
+    pub fn new(seed: impl Display) -> Self {
         Self {
-            xg: Alea::new(seed),
+            xg: Alea::new(&seed),
         }
     }
 
+    /// Restore a generator from a state saved with [`Prng::get_state`].
+    pub fn from_state(state: impl Into<Alea>) -> Self {
+        Self { xg: state.into() }
+    }
+
     pub fn gen_next(&mut self) -> f64 {
         self.xg.next().unwrap()
     }
 
+    pub fn quick(&mut self) -> f64 {
+        self.gen_next()
+    }
+
     pub fn int32(&mut self) -> i32 {
         wrap_to_i32(self.gen_next() * TWO_TO_THE_POWER_OF_32 as f64)
     }
 
     pub fn double(&mut self) -> f64 {
-        ((self.gen_next() * TWO_TO_THE_POWER_OF_21 as f64) as u64 as f64)
-            .mul_add(TWO_TO_THE_POWER_OF_MINUS_53, self.gen_next())
+        // seedrandom draws the low bits first: `prng() + (prng() * 0x200000 | 0) * 2^-53`
+        let low = self.gen_next();
+        let high = (self.gen_next() * TWO_TO_THE_POWER_OF_21 as f64) as u64 as f64;
+        high.mul_add(TWO_TO_THE_POWER_OF_MINUS_53, low)
     }
 
     pub fn get_state(&self) -> AleaState {
@@ -147,6 +175,38 @@ impl Prng {
     }
 }
 
+/// `next_u32` matches `prng.int32() >>> 0` of the JavaScript generator.
+#[cfg(feature = "rand_core")]
+impl rand_core::RngCore for Prng {
+    fn next_u32(&mut self) -> u32 {
+        self.int32() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dst)
+    }
+}
+
+#[cfg(feature = "rand_core")]
+impl rand_core::SeedableRng for Prng {
+    type Seed = [u8; 32];
+
+    /// Seeds like `alea(String.fromCharCode(...seed))` in JavaScript.
+    fn from_seed(seed: Self::Seed) -> Self {
+        let seed: alloc::string::String = seed.iter().map(|&byte| byte as char).collect();
+        Self::new(seed)
+    }
+
+    /// Seeds like `alea(String(state))` in JavaScript.
+    fn seed_from_u64(state: u64) -> Self {
+        Self::new(state)
+    }
+}
+
 // The rem_euclid() wraps within a positive range, then casting u32 to i32 makes half of that range negative.
 fn wrap_to_i32(input: f64) -> i32 {
     input.rem_euclid((u32::MAX as f64) + 1.0) as u32 as i32
@@ -1,10 +1,20 @@
 use crate::ImplScheduler;
-use crate::models::{Card, Rating, RecordLog, SchedulingInfo};
+use crate::fractional_days::{FractionalDays, try_fractional_days};
+use crate::fuzz::Fuzz;
+use crate::models::State;
+use crate::models::{Card, Rating, ReviewLog, SchedulingInfo};
 use crate::parameters::Parameters;
+use crate::record_log::RecordLog;
 use crate::scheduler_basic::BasicScheduler;
 use crate::scheduler_longterm::LongtermScheduler;
+use crate::siblings::space_from_siblings;
 
-use chrono::{DateTime, Utc};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, Utc};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
 #[derive(Debug, Default, Clone)]
 pub struct FSRS {
@@ -16,19 +26,234 @@ impl FSRS {
         Self { parameters }
     }
 
-    pub fn scheduler(&self, card: Card, now: DateTime<Utc>) -> Box<dyn ImplScheduler> {
+    pub const fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+
+    pub fn with_fuzz(mut self, fuzz: impl Fuzz + 'static) -> Self {
+        self.parameters.fuzz = Arc::new(fuzz);
+        self
+    }
+
+    pub fn scheduler(&self, card: Card, now: DateTime<Utc>) -> Box<dyn ImplScheduler + '_> {
+        if self.parameters.enable_short_term {
+            Box::new(BasicScheduler::new(&self.parameters, card, now))
+        } else {
+            Box::new(LongtermScheduler::new(&self.parameters, card, now))
+        }
+    }
+
+    /// Run `f` on a scheduler on the stack.
+    fn schedule<R>(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        f: impl FnOnce(&mut dyn ImplScheduler) -> R,
+    ) -> R {
+        self.schedule_with_retention(card, now, self.parameters.request_retention, f)
+    }
+
+    fn schedule_with_retention<R>(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        request_retention: f64,
+        f: impl FnOnce(&mut dyn ImplScheduler) -> R,
+    ) -> R {
         if self.parameters.enable_short_term {
-            Box::new(BasicScheduler::new(self.parameters.clone(), card, now))
+            f(&mut BasicScheduler::new(&self.parameters, card, now)
+                .with_retention(request_retention))
         } else {
-            Box::new(LongtermScheduler::new(self.parameters.clone(), card, now))
+            f(&mut LongtermScheduler::new(&self.parameters, card, now)
+                .with_retention(request_retention))
         }
     }
 
+    /// Schedule every card, in parallel with the `rayon` feature. `f` gets the
+    /// index of the card in `cards`.
+    fn batch<R, F>(&self, cards: &[Card], now: DateTime<Utc>, f: F) -> Vec<R>
+    where
+        R: Send,
+        F: Fn(&mut dyn ImplScheduler, usize) -> R + Sync,
+    {
+        #[cfg(feature = "rayon")]
+        let cards = cards.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let cards = cards.iter();
+
+        cards
+            .enumerate()
+            .map(|(index, card)| self.schedule(card.clone(), now, |scheduler| f(scheduler, index)))
+            .collect()
+    }
+
     pub fn repeat(&self, card: Card, now: DateTime<Utc>) -> RecordLog {
-        self.scheduler(card, now).preview()
+        self.schedule(card, now, |scheduler| scheduler.preview())
     }
 
     pub fn next(&self, card: Card, now: DateTime<Utc>, rating: Rating) -> SchedulingInfo {
-        self.scheduler(card, now).review(rating)
+        self.schedule(card, now, |scheduler| scheduler.review(rating))
+    }
+
+    /// [`FSRS::repeat`] with intervals computed for `request_retention` instead of
+    /// `Parameters::request_retention`, e.g. for exam-critical cards.
+    pub fn repeat_with_retention(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        request_retention: f64,
+    ) -> RecordLog {
+        self.schedule_with_retention(card, now, request_retention, |scheduler| {
+            scheduler.preview()
+        })
+    }
+
+    /// [`FSRS::next`] with intervals computed for `request_retention`.
+    pub fn next_with_retention(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        rating: Rating,
+        request_retention: f64,
+    ) -> SchedulingInfo {
+        self.schedule_with_retention(card, now, request_retention, |scheduler| {
+            scheduler.review(rating)
+        })
+    }
+
+    /// [`FSRS::next`], recording that the answer took `review_duration` milliseconds.
+    pub fn next_with_duration(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        rating: Rating,
+        review_duration: u32,
+    ) -> SchedulingInfo {
+        let mut info = self.next(card, now, rating);
+        info.review_log.review_duration = Some(review_duration);
+        info
+    }
+
+    /// [`FSRS::repeat`] for every card, in order.
+    pub fn repeat_batch(&self, cards: &[Card], now: DateTime<Utc>) -> Vec<RecordLog> {
+        self.batch(cards, now, |scheduler, _| scheduler.preview())
+    }
+
+    /// [`FSRS::next`] for every card with the rating at the same index.
+    ///
+    /// # Panics
+    ///
+    /// If `cards` and `ratings` have different lengths.
+    pub fn next_batch(
+        &self,
+        cards: &[Card],
+        now: DateTime<Utc>,
+        ratings: &[Rating],
+    ) -> Vec<SchedulingInfo> {
+        assert_eq!(cards.len(), ratings.len(), "one rating per card");
+        self.batch(cards, now, |scheduler, index| {
+            scheduler.review(ratings[index])
+        })
+    }
+
+    /// [`Card::get_retrievability`] for every card, in order, with this scheduler's
+    /// `decay` and `factor`.
+    pub fn retrievability_batch(&self, cards: &[Card], now: DateTime<Utc>) -> Vec<f64> {
+        #[cfg(feature = "rayon")]
+        let cards = cards.par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let cards = cards.iter();
+
+        cards
+            .map(|card| match card.state {
+                State::New => 0.0,
+                _ => {
+                    let elapsed_days = (now - card.last_review).num_days();
+                    self.parameters
+                        .retrievability(elapsed_days as f64, card.stability)
+                }
+            })
+            .collect()
+    }
+
+    /// Retrievability of `card` at `at`, counting fractional days since its last review.
+    /// The schedulers and [`Card::get_retrievability`] count whole days instead.
+    pub fn retrievability_at(&self, card: &Card, at: DateTime<Utc>) -> f64 {
+        if card.state == State::New {
+            return 0.0;
+        }
+        let elapsed_days = (at - card.last_review).num_fractional_days().max(0.0);
+        self.parameters.retrievability(elapsed_days, card.stability)
+    }
+
+    /// When the retrievability of `card` drops to `retrievability`, or `None` for
+    /// new cards, targets outside `(0, 1]` and dates too far away to represent.
+    pub fn retrievability_date(&self, card: &Card, retrievability: f64) -> Option<DateTime<Utc>> {
+        if card.state == State::New || !(retrievability > 0.0 && retrievability <= 1.0) {
+            return None;
+        }
+        let days = self
+            .parameters
+            .elapsed_days_for_retrievability(card.stability, retrievability);
+        card.last_review
+            .checked_add_signed(try_fractional_days(days)?)
+    }
+
+    /// Retrievability of `card` from `start` to `end` inclusive, every `step`.
+    /// Empty if `step` is not positive, and stops at the latest representable date.
+    pub fn retrievability_curve(
+        &self,
+        card: &Card,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step: Duration,
+    ) -> Vec<(DateTime<Utc>, f64)> {
+        if step <= Duration::zero() {
+            return Vec::new();
+        }
+        let mut curve = Vec::new();
+        let mut at = Some(start);
+        while let Some(date) = at.filter(|date| *date <= end) {
+            curve.push((date, self.retrievability_at(card, date)));
+            at = date.checked_add_signed(step);
+        }
+        curve
+    }
+
+    /// Un-fuzzed interval `card` would get if the requested retention were `retention`.
+    pub fn interval_for_retention(&self, card: &Card, retention: f64) -> f64 {
+        self.parameters
+            .interval_for_retention(card.stability, retention)
+    }
+
+    /// Rebuild a card by reviewing it again with the ratings and dates of its logs.
+    pub fn replay<'a>(&self, card: Card, logs: impl IntoIterator<Item = &'a ReviewLog>) -> Card {
+        logs.into_iter().fold(card, |card, log| {
+            self.next(card, log.reviewed_date, log.rating).card
+        })
+    }
+
+    /// [`FSRS::repeat`], with the review intervals moved away from the due dates of
+    /// `siblings` while keeping the ratings in order.
+    pub fn repeat_with_siblings(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        siblings: &[Card],
+    ) -> RecordLog {
+        let mut record_log = self.repeat(card, now);
+        space_from_siblings(&self.parameters, &mut record_log, now, siblings);
+        record_log
+    }
+
+    /// The `rating` entry of [`FSRS::repeat_with_siblings`].
+    pub fn next_with_siblings(
+        &self,
+        card: Card,
+        now: DateTime<Utc>,
+        rating: Rating,
+        siblings: &[Card],
+    ) -> SchedulingInfo {
+        self.repeat_with_siblings(card, now, siblings)[rating].clone()
     }
 }
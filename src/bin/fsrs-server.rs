@@ -0,0 +1,354 @@
+//! HTTP JSON scheduling service for applications written in other languages.
+//!
+//! Every endpoint takes a `POST` with a JSON body and answers with JSON.
+//! `parameters` and `now` are optional in every request body; failures are
+//! reported as `{"error": {"code": ..., "message": ...}}` with a matching status.
+
+use std::env;
+use std::io::Read;
+use std::panic::catch_unwind;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Utc};
+use rs_fsrs::{Card, FSRS, Parameters, Rating, ReviewLog};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+/// Largest request body accepted, in bytes
+const MAX_BODY: u64 = 1 << 20;
+
+#[derive(Deserialize)]
+struct RepeatRequest {
+    card: Card,
+    now: Option<DateTime<Utc>>,
+    #[serde(default)]
+    parameters: Parameters,
+}
+
+#[derive(Deserialize)]
+struct NextRequest {
+    card: Card,
+    rating: Rating,
+    now: Option<DateTime<Utc>>,
+    #[serde(default)]
+    parameters: Parameters,
+}
+
+#[derive(Deserialize)]
+struct RetrievabilityRequest {
+    card: Card,
+    now: Option<DateTime<Utc>>,
+    #[serde(default)]
+    parameters: Parameters,
+}
+
+#[derive(Deserialize)]
+struct ReplayRequest {
+    card: Option<Card>,
+    logs: Vec<ReviewLog>,
+    #[serde(default)]
+    parameters: Parameters,
+}
+
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "error": { "code": self.code, "message": self.message } })
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, ApiError> {
+    serde_json::from_str(body)
+        .map_err(|error| ApiError::new(400, "invalid_request", error.to_string()))
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, ApiError> {
+    serde_json::to_value(value).map_err(|error| ApiError::new(500, "internal", error.to_string()))
+}
+
+/// Scheduler for request parameters, rejecting values that would give NaN or nonsense intervals.
+fn scheduler(parameters: Parameters) -> Result<FSRS, ApiError> {
+    if !(parameters.request_retention > 0.0 && parameters.request_retention < 1.0) {
+        return Err(ApiError::new(
+            400,
+            "invalid_parameters",
+            "request_retention must be between 0 and 1",
+        ));
+    }
+    if parameters.maximum_interval <= 0 {
+        return Err(ApiError::new(
+            400,
+            "invalid_parameters",
+            "maximum_interval must be positive",
+        ));
+    }
+    Ok(FSRS::new(parameters))
+}
+
+fn handle(method: &Method, url: &str, body: &str) -> Result<Value, ApiError> {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    if !matches!(path, "/repeat" | "/next" | "/retrievability" | "/replay") {
+        return Err(ApiError::new(
+            404,
+            "not_found",
+            format!("no endpoint at {path}"),
+        ));
+    }
+    if *method != Method::Post {
+        return Err(ApiError::new(405, "method_not_allowed", "use POST"));
+    }
+
+    match path {
+        "/repeat" => {
+            let request: RepeatRequest = parse(body)?;
+            let now = request.now.unwrap_or_else(Utc::now);
+            to_value(scheduler(request.parameters)?.repeat(request.card, now))
+        }
+        "/next" => {
+            let request: NextRequest = parse(body)?;
+            let now = request.now.unwrap_or_else(Utc::now);
+            to_value(scheduler(request.parameters)?.next(request.card, now, request.rating))
+        }
+        "/retrievability" => {
+            let request: RetrievabilityRequest = parse(body)?;
+            let now = request.now.unwrap_or_else(Utc::now);
+            let retrievability =
+                scheduler(request.parameters)?.retrievability_at(&request.card, now);
+            Ok(json!({ "retrievability": retrievability }))
+        }
+        _ => {
+            let request: ReplayRequest = parse(body)?;
+            let start = request
+                .logs
+                .first()
+                .map_or_else(Utc::now, |log| log.reviewed_date);
+            let card = request.card.unwrap_or(Card {
+                due: start,
+                last_review: start,
+                ..Default::default()
+            });
+            to_value(scheduler(request.parameters)?.replay(card, &request.logs))
+        }
+    }
+}
+
+fn respond(mut request: Request) {
+    let mut body = String::new();
+    let result = match request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+    {
+        Ok(read) if read as u64 > MAX_BODY => Err(ApiError::new(
+            413,
+            "payload_too_large",
+            format!("request body is larger than {MAX_BODY} bytes"),
+        )),
+        Ok(_) => {
+            let (method, url) = (request.method(), request.url());
+            catch_unwind(|| handle(method, url, &body))
+                .unwrap_or_else(|_| Err(ApiError::new(500, "internal", "scheduling failed")))
+        }
+        Err(error) => Err(ApiError::new(400, "invalid_request", error.to_string())),
+    };
+    let (status, value) = match result {
+        Ok(value) => (200, value),
+        Err(error) => (error.status, error.to_json()),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    if let Err(error) = request.respond(response) {
+        eprintln!("failed to respond: {error}");
+    }
+}
+
+fn serve(server: &Server) {
+    for request in server.incoming_requests() {
+        respond(request);
+    }
+}
+
+fn main() -> ExitCode {
+    let addr = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    match Server::http(&addr) {
+        Ok(server) => {
+            eprintln!("listening on http://{addr}");
+            serve(&server);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: cannot listen on {addr}: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::net::{SocketAddr, TcpStream};
+    use std::sync::Arc;
+    use std::thread;
+
+    fn start() -> SocketAddr {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let addr = server.server_addr().to_ip().unwrap();
+        thread::spawn(move || serve(&server));
+        addr
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+             Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    fn card_json() -> Value {
+        let now = "2022-11-29T12:30:00Z".parse().unwrap();
+        serde_json::to_value(Card {
+            due: now,
+            last_review: now,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_next_and_repeat() {
+        let addr = start();
+        let body = json!({
+            "card": card_json(),
+            "rating": "Easy",
+            "now": "2022-11-29T12:30:00Z",
+            "parameters": { "request_retention": 0.9 },
+        });
+        let (status, next) = request(addr, "POST", "/next", &body.to_string());
+        assert_eq!(status, 200);
+        assert_eq!(next["card"]["state"], "Review");
+
+        let (status, repeat) = request(addr, "POST", "/repeat", &body.to_string());
+        assert_eq!(status, 200);
+        assert_eq!(repeat["Easy"]["card"], next["card"]);
+
+        let body = json!({ "card": next["card"], "now": next["card"]["due"] });
+        let (status, value) = request(addr, "POST", "/retrievability", &body.to_string());
+        assert_eq!(status, 200);
+        assert!((value["retrievability"].as_f64().unwrap() - 0.9026208).abs() < 1e-7);
+
+        let body = json!({ "logs": [next["review_log"]] });
+        let (status, card) = request(addr, "POST", "/replay", &body.to_string());
+        assert_eq!(status, 200);
+        assert_eq!(card, next["card"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let addr = start();
+        let (status, value) = request(addr, "POST", "/next", "{\"card\": 1}");
+        assert_eq!(status, 400);
+        assert_eq!(value["error"]["code"], "invalid_request");
+
+        let (status, value) = request(addr, "GET", "/next", "");
+        assert_eq!(status, 405);
+        assert_eq!(value["error"]["code"], "method_not_allowed");
+
+        let (status, value) = request(addr, "POST", "/missing", "{}");
+        assert_eq!(status, 404);
+        assert_eq!(value["error"]["code"], "not_found");
+
+        let body = json!({ "card": card_json(), "parameters": { "request_retention": 1.5 } });
+        let (status, value) = request(addr, "POST", "/repeat", &body.to_string());
+        assert_eq!(status, 400);
+        assert_eq!(value["error"]["code"], "invalid_parameters");
+
+        let body = json!({ "card": card_json(), "parameters": { "maximum_interval": 0 } });
+        let (status, value) = request(addr, "POST", "/repeat", &body.to_string());
+        assert_eq!(status, 400);
+        assert_eq!(value["error"]["code"], "invalid_parameters");
+
+        let body = " ".repeat(MAX_BODY as usize + 1);
+        let (status, value) = request(addr, "POST", "/repeat", &body);
+        assert_eq!(status, 413);
+        assert_eq!(value["error"]["code"], "payload_too_large");
+    }
+
+    #[test]
+    fn test_query_string() {
+        let addr = start();
+        let body = json!({ "card": card_json(), "now": "2022-11-29T12:30:00Z" });
+        let (status, value) = request(addr, "POST", "/retrievability?v=1", &body.to_string());
+        assert_eq!(status, 200);
+        assert!(value["retrievability"].is_number());
+    }
+
+    #[test]
+    fn test_retrievability_parameters() {
+        let addr = start();
+        let card = json!({
+            "due": "2022-12-09T12:30:00Z",
+            "stability": 10.0,
+            "difficulty": 5.0,
+            "elapsed_days": 10,
+            "scheduled_days": 10,
+            "reps": 2,
+            "lapses": 0,
+            "state": "Review",
+            "last_review": "2022-11-29T12:30:00Z",
+        });
+        let retrievability = |parameters: Value| {
+            let body = json!({
+                "card": card,
+                "now": "2022-12-19T12:30:00Z",
+                "parameters": parameters,
+            });
+            let (status, value) = request(addr, "POST", "/retrievability", &body.to_string());
+            assert_eq!(status, 200);
+            value["retrievability"].as_f64().unwrap()
+        };
+
+        let default = retrievability(json!({}));
+        let slow = Parameters {
+            decay: -0.2,
+            factor: 0.9f64.powi(-5) - 1.0,
+            ..Default::default()
+        };
+        let custom = retrievability(json!({ "decay": slow.decay, "factor": slow.factor }));
+        assert_eq!(default, Parameters::default().retrievability(20.0, 10.0));
+        assert_eq!(custom, slow.retrievability(20.0, 10.0));
+        assert!(custom > default + 1e-3);
+    }
+}
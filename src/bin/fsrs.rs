@@ -0,0 +1,326 @@
+//! Command-line tool to inspect and fix cards without writing Rust.
+//!
+//! Cards, scheduling results and parameters are JSON; review logs can also be
+//! read as CSV and simulations written as CSV.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use chrono::{DateTime, Duration, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use rs_fsrs::{Card, FSRS, Parameters, Rating, ReviewLog, Seed, State, alea};
+use serde::Serialize;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[derive(Parser)]
+#[command(name = "fsrs", version, about = "Inspect and schedule FSRS cards")]
+struct Cli {
+    /// JSON file with the parameters to schedule with
+    #[arg(long, global = true)]
+    parameters: Option<PathBuf>,
+
+    /// Review time as RFC 3339, defaults to the current time
+    #[arg(long, global = true)]
+    now: Option<DateTime<Utc>>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show the outcome of every rating for the card read from stdin
+    Preview,
+    /// Apply a rating to the card read from stdin
+    Review {
+        #[arg(long, value_enum)]
+        rating: CliRating,
+    },
+    /// Rebuild a card from its review logs
+    Replay {
+        /// Log file, stdin if omitted
+        file: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+    /// Print the retrievability of the card (or array of cards) read from stdin
+    Retrievability,
+    /// Simulate reviewing a deck, printing one row per day
+    Simulate {
+        #[arg(long, default_value_t = 1000)]
+        cards: usize,
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+        #[arg(long, default_value_t = 20)]
+        new_per_day: usize,
+        #[arg(long, default_value = "fsrs")]
+        seed: String,
+        #[arg(long, value_enum, default_value_t = Format::Csv)]
+        format: Format,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CliRating {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl From<CliRating> for Rating {
+    fn from(rating: CliRating) -> Self {
+        match rating {
+            CliRating::Again => Self::Again,
+            CliRating::Hard => Self::Hard,
+            CliRating::Good => Self::Good,
+            CliRating::Easy => Self::Easy,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct SimulationDay {
+    day: u32,
+    new: usize,
+    reviews: usize,
+    lapses: usize,
+    memorized: f64,
+}
+
+fn read_stdin() -> Result<String> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+fn write_json(value: &impl Serialize) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, value)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+fn load_parameters(path: Option<&PathBuf>) -> Result<Parameters> {
+    match path {
+        Some(path) => Ok(serde_json::from_reader(File::open(path)?)?),
+        None => Ok(Parameters::default()),
+    }
+}
+
+fn read_logs(input: impl Read, format: Format) -> Result<Vec<ReviewLog>> {
+    match format {
+        Format::Json => Ok(serde_json::from_reader(input)?),
+        Format::Csv => Ok(csv::Reader::from_reader(input)
+            .deserialize()
+            .collect::<std::result::Result<_, _>>()?),
+    }
+}
+
+fn retrievability(fsrs: &FSRS, input: &str, now: DateTime<Utc>) -> Result<serde_json::Value> {
+    let value: serde_json::Value = serde_json::from_str(input)?;
+    if value.is_array() {
+        let cards: Vec<Card> = serde_json::from_value(value)?;
+        let retrievability: Vec<f64> = cards
+            .iter()
+            .map(|card| fsrs.retrievability_at(card, now))
+            .collect();
+        Ok(retrievability.into())
+    } else {
+        let card: Card = serde_json::from_value(value)?;
+        Ok(fsrs.retrievability_at(&card, now).into())
+    }
+}
+
+/// Reviews a deck day by day, recalling each card with probability equal to its retrievability.
+fn simulate(
+    fsrs: &FSRS,
+    start: DateTime<Utc>,
+    cards: usize,
+    days: u32,
+    new_per_day: usize,
+    seed: &str,
+) -> Vec<SimulationDay> {
+    let mut generator = alea(Seed::new(seed));
+    // both counts come from the command line, so don't allocate for them up front
+    let mut deck: Vec<Card> = Vec::new();
+    let mut history = Vec::new();
+
+    for day in 0..days {
+        let now = start + Duration::days(day.into());
+        let mut stats = SimulationDay {
+            day,
+            new: 0,
+            reviews: 0,
+            lapses: 0,
+            memorized: 0.0,
+        };
+
+        for card in deck.iter_mut().filter(|card| card.due <= now) {
+            let recalled = generator.quick() < fsrs.retrievability_at(card, now);
+            let rating = if recalled {
+                Rating::Good
+            } else {
+                Rating::Again
+            };
+            if !recalled && card.state == State::Review {
+                stats.lapses += 1;
+            }
+            *card = fsrs.next(card.clone(), now, rating).card;
+            stats.reviews += 1;
+        }
+
+        while stats.new < new_per_day && deck.len() < cards {
+            let card = Card {
+                due: now,
+                last_review: now,
+                ..Default::default()
+            };
+            deck.push(fsrs.next(card, now, Rating::Good).card);
+            stats.new += 1;
+        }
+
+        stats.memorized = deck
+            .iter()
+            .map(|card| fsrs.retrievability_at(card, now))
+            .sum();
+        history.push(stats);
+    }
+    history
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let fsrs = FSRS::new(load_parameters(cli.parameters.as_ref())?);
+    let now = cli.now.unwrap_or_else(Utc::now);
+
+    match cli.command {
+        Command::Preview => {
+            let card: Card = serde_json::from_str(&read_stdin()?)?;
+            write_json(&fsrs.repeat(card, now))
+        }
+        Command::Review { rating } => {
+            let card: Card = serde_json::from_str(&read_stdin()?)?;
+            write_json(&fsrs.next(card, now, rating.into()))
+        }
+        Command::Replay { file, format } => {
+            let logs = match file {
+                Some(path) => read_logs(File::open(path)?, format)?,
+                None => read_logs(io::stdin().lock(), format)?,
+            };
+            let start = logs.first().map_or(now, |log| log.reviewed_date);
+            let card = Card {
+                due: start,
+                last_review: start,
+                ..Default::default()
+            };
+            write_json(&fsrs.replay(card, &logs))
+        }
+        Command::Retrievability => write_json(&retrievability(&fsrs, &read_stdin()?, now)?),
+        Command::Simulate {
+            cards,
+            days,
+            new_per_day,
+            seed,
+            format,
+        } => {
+            let history = simulate(&fsrs, now, cards, days, new_per_day, &seed);
+            match format {
+                Format::Json => write_json(&history),
+                Format::Csv => {
+                    let mut writer = csv::Writer::from_writer(io::stdout().lock());
+                    for day in &history {
+                        writer.serialize(day)?;
+                    }
+                    writer.flush()?;
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        "2022-11-29T12:30:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_read_csv_logs() {
+        let csv = "\
+rating,elapsed_days,scheduled_days,state,reviewed_date
+Good,0,0,New,2022-11-29T12:30:00Z
+Good,0,0,Learning,2022-11-29T12:40:00Z
+";
+        let logs = read_logs(csv.as_bytes(), Format::Csv).unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[1].state, State::Learning);
+
+        let card = FSRS::default().replay(Card::new(), &logs);
+        assert_eq!(card.state, State::Review);
+        assert_eq!(card.reps, 2);
+    }
+
+    #[test]
+    fn test_retrievability() {
+        let fsrs = FSRS::default();
+        let card = fsrs.next(Card::new(), now(), Rating::Easy).card;
+        let json = serde_json::to_string(&[&card, &card]).unwrap();
+        let value = retrievability(&fsrs, &json, card.due).unwrap();
+        assert_eq!(value.as_array().unwrap().len(), 2);
+
+        let json = serde_json::to_string(&card).unwrap();
+        let value = retrievability(&fsrs, &json, card.due).unwrap();
+        assert!((value.as_f64().unwrap() - 0.9026208).abs() < 1e-7);
+
+        // the decay and factor of --parameters are used
+        let slow = FSRS::new(Parameters {
+            decay: -0.2,
+            factor: 0.9f64.powi(-5) - 1.0,
+            ..Default::default()
+        });
+        let later = card.due + Duration::days(30);
+        let value = retrievability(&slow, &json, later).unwrap();
+        assert_eq!(value.as_f64(), Some(slow.retrievability_at(&card, later)));
+        assert!(value.as_f64().unwrap() > fsrs.retrievability_at(&card, later) + 1e-3);
+    }
+
+    #[test]
+    fn test_simulate() {
+        let fsrs = FSRS::default();
+        let history = simulate(&fsrs, now(), 50, 30, 10, "seed");
+
+        assert_eq!(history.len(), 30);
+        assert_eq!(history.iter().map(|day| day.new).sum::<usize>(), 50);
+        assert!(history.iter().all(|day| day.memorized <= 50.0));
+        assert_eq!(history, simulate(&fsrs, now(), 50, 30, 10, "seed"));
+    }
+
+    #[test]
+    fn test_simulate_negative_days() {
+        assert!(Cli::try_parse_from(["fsrs", "simulate", "--days", "-1"]).is_err());
+        assert!(Cli::try_parse_from(["fsrs", "simulate", "--days", "1"]).is_ok());
+    }
+}
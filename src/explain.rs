@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::{Card, FSRS, IntervalInfo, Parameters, Rating, ReviewTiming, Scheduler, State};
+
+/// How the new stability was derived from the old memory state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum StabilityUpdate {
+    /// First review: `w[rating - 1]`
+    Initial,
+    /// Same-day review in (re)learning: `S * exp(w17 * (rating - 3 + w18))`
+    ShortTerm,
+    /// Same-day review with `early_review_damping`:
+    /// `S * exp(w17 * (rating - 3 + w18)) * S^-damping`, at least `S` for `Good` and `Easy`
+    SameDay,
+    /// Successful review: `S * growth`
+    Recall(RecallTerms),
+    /// Lapse: `w11 * D^-w12 * ((S + 1)^w13 - 1) * exp(w14 * (1 - R))`
+    Forget,
+}
+
+/// Factors of the stability growth `1 + exp(w8) * (11 - D) * S^-w9 * (exp(w10 * (1 - R)) - 1) * modifier`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct RecallTerms {
+    /// `11 - D`
+    pub difficulty_term: f64,
+    /// `S^-w9`
+    pub stability_term: f64,
+    /// `exp(w10 * (1 - R)) - 1`
+    pub retrievability_term: f64,
+    /// `w15` for `Hard`, `w16` for `Easy`, otherwise 1
+    pub rating_modifier: f64,
+    /// Factor the stability was multiplied by
+    pub growth: f64,
+}
+
+impl RecallTerms {
+    fn new(
+        parameters: &Parameters,
+        difficulty: f64,
+        stability: f64,
+        retrievability: f64,
+        rating: Rating,
+    ) -> Self {
+        let w = &parameters.w;
+        let rating_modifier = match rating {
+            Rating::Hard => w[15],
+            Rating::Easy => w[16],
+            _ => 1.0,
+        };
+        let difficulty_term = 11.0 - difficulty;
+        let stability_term = stability.powf(-w[9]);
+        let retrievability_term = ((1.0 - retrievability) * w[10]).exp_m1();
+        let growth = (w[8].exp() * difficulty_term * stability_term * retrievability_term)
+            .mul_add(rating_modifier, 1.0);
+        Self {
+            difficulty_term,
+            stability_term,
+            retrievability_term,
+            rating_modifier,
+            growth,
+        }
+    }
+}
+
+/// Every quantity the scheduler used to answer a card with a rating.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Explanation {
+    pub rating: Rating,
+    pub state: State,
+    pub next_state: State,
+    /// Days since the last review
+    pub elapsed_days: i64,
+    pub timing: ReviewTiming,
+    /// Retrievability at the time of the review, as limited by `overdue_cap`
+    pub retrievability: f64,
+    pub difficulty: f64,
+    pub next_difficulty: f64,
+    pub stability: f64,
+    pub next_stability: f64,
+    pub stability_update: StabilityUpdate,
+    /// Interval reaching the requested retention before rounding and clamping, `None` for learning steps
+    pub ideal_interval: Option<f64>,
+    pub maximum_interval: i32,
+    /// Whether the interval was cut to `maximum_interval`
+    pub clamped: bool,
+    /// Rounded interval, fuzz range and fuzz factor, `None` for learning steps
+    pub interval: Option<IntervalInfo>,
+    /// Days until due after fuzzing and keeping the ratings in order
+    pub scheduled_days: i64,
+    pub due: DateTime<Utc>,
+}
+
+impl FSRS {
+    /// Explain how [`FSRS::next`] schedules `card` for `rating` at `now`.
+    pub fn explain(&self, card: Card, now: DateTime<Utc>, rating: Rating) -> Explanation {
+        let parameters = self.parameters();
+        let scheduler = Scheduler::new(parameters, card.clone(), now);
+        let retrievability = scheduler.retrievability();
+        let info = self.next(card.clone(), now, rating);
+
+        let stability_update = match card.state {
+            State::New => StabilityUpdate::Initial,
+            State::Learning | State::Relearning if parameters.enable_short_term => {
+                StabilityUpdate::ShortTerm
+            }
+            _ if scheduler.same_day_damping().is_some() => StabilityUpdate::SameDay,
+            _ if rating == Rating::Again => StabilityUpdate::Forget,
+            _ => StabilityUpdate::Recall(RecallTerms::new(
+                parameters,
+                card.difficulty,
+                card.stability,
+                retrievability,
+                rating,
+            )),
+        };
+        let ideal_interval = info
+            .interval
+            .map(|_| parameters.ideal_interval(info.card.stability));
+
+        Explanation {
+            rating,
+            state: card.state,
+            next_state: info.card.state,
+            elapsed_days: info.card.elapsed_days,
+            timing: info.review_log.timing,
+            retrievability,
+            difficulty: card.difficulty,
+            next_difficulty: info.card.difficulty,
+            stability: card.stability,
+            next_stability: info.card.stability,
+            stability_update,
+            ideal_interval,
+            maximum_interval: parameters.maximum_interval,
+            clamped: ideal_interval
+                .is_some_and(|interval| interval.round() > parameters.maximum_interval as f64),
+            interval: info.interval,
+            scheduled_days: info.card.scheduled_days,
+            due: info.card.due,
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:?} on a {:?} card -> {:?}",
+            self.rating, self.state, self.next_state
+        )?;
+        writeln!(
+            f,
+            "elapsed days: {} ({:?}), retrievability: {:.4}",
+            self.elapsed_days, self.timing, self.retrievability
+        )?;
+        writeln!(
+            f,
+            "difficulty: {:.4} -> {:.4}",
+            self.difficulty, self.next_difficulty
+        )?;
+        writeln!(
+            f,
+            "stability: {:.4} -> {:.4} ({:?})",
+            self.stability, self.next_stability, self.stability_update
+        )?;
+        if let (Some(ideal), Some(interval)) = (self.ideal_interval, self.interval) {
+            writeln!(
+                f,
+                "interval: {ideal:.2} -> {} (maximum {}{})",
+                interval.raw_interval,
+                self.maximum_interval,
+                if self.clamped { ", clamped" } else { "" }
+            )?;
+            write!(
+                f,
+                "fuzz range: {}..={}",
+                interval.min_interval, interval.max_interval
+            )?;
+            if let Some(factor) = interval.fuzz_factor {
+                write!(f, ", factor {factor:.4}")?;
+            }
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "scheduled days: {}, due: {}",
+            self.scheduled_days,
+            self.due.to_rfc3339()
+        )
+    }
+}
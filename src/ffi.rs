@@ -0,0 +1,527 @@
+//! C ABI for embedding the scheduler in non-Rust applications.
+//!
+//! Timestamps are milliseconds since the Unix epoch, ratings and states use the
+//! numeric values of [`Rating`] and [`State`], and a card's queue status is 0
+//! (active), 1 (suspended) or 2 (buried until `buried_until`). Every function returns an
+//! [`FsrsStatus`] instead of panicking across the FFI boundary.
+//!
+//! The struct layouts are versioned by [`FSRS_ABI_VERSION`]; compare it with
+//! [`fsrs_abi_version`] before passing structs to a library built from another header.
+
+use std::panic::{UnwindSafe, catch_unwind};
+use std::ptr;
+
+use chrono::{DateTime, Utc};
+
+use crate::{Card, FSRS, Parameters, QueueStatus, Rating, ReviewLog, SchedulingInfo, State};
+
+/// Bumped whenever a struct in this module changes layout, together with the
+/// `#define` in cbindgen.toml.
+pub const FSRS_ABI_VERSION: u32 = 2;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FsrsStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidRating = 2,
+    InvalidState = 3,
+    InvalidTimestamp = 4,
+    Panic = 5,
+    InvalidQueueStatus = 6,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct FsrsParameters {
+    pub request_retention: f64,
+    pub maximum_interval: i32,
+    pub w: [f64; 19],
+    pub enable_short_term: bool,
+    pub enable_fuzz: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsrsCard {
+    /// Whether the card has an `id`
+    pub has_id: bool,
+    pub id: i64,
+    /// Whether the card has a `preset_id`, the default preset is used otherwise
+    pub has_preset_id: bool,
+    pub preset_id: i64,
+    pub due: i64,
+    pub stability: f64,
+    pub difficulty: f64,
+    pub elapsed_days: i64,
+    pub scheduled_days: i64,
+    pub reps: i32,
+    pub lapses: i32,
+    pub state: i32,
+    pub last_review: i64,
+    pub status: i32,
+    /// End of the burial when `status` is 2, ignored otherwise
+    pub buried_until: i64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsrsReviewLog {
+    pub has_card_id: bool,
+    pub card_id: i64,
+    pub has_preset_id: bool,
+    pub preset_id: i64,
+    pub rating: i32,
+    pub elapsed_days: i64,
+    pub scheduled_days: i64,
+    pub state: i32,
+    pub reviewed_date: i64,
+    /// Milliseconds taken to answer, -1 if not recorded
+    pub review_duration: i64,
+    /// 0 (early), 1 (on time) or 2 (late)
+    pub timing: i32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsrsSchedulingInfo {
+    pub card: FsrsCard,
+    pub review_log: FsrsReviewLog,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FsrsRecordLog {
+    pub again: FsrsSchedulingInfo,
+    pub hard: FsrsSchedulingInfo,
+    pub good: FsrsSchedulingInfo,
+    pub easy: FsrsSchedulingInfo,
+}
+
+impl From<&Parameters> for FsrsParameters {
+    fn from(parameters: &Parameters) -> Self {
+        Self {
+            request_retention: parameters.request_retention,
+            maximum_interval: parameters.maximum_interval,
+            w: parameters.w,
+            enable_short_term: parameters.enable_short_term,
+            enable_fuzz: parameters.enable_fuzz,
+        }
+    }
+}
+
+impl From<&FsrsParameters> for Parameters {
+    fn from(parameters: &FsrsParameters) -> Self {
+        Self {
+            request_retention: parameters.request_retention,
+            maximum_interval: parameters.maximum_interval,
+            w: parameters.w,
+            enable_short_term: parameters.enable_short_term,
+            enable_fuzz: parameters.enable_fuzz,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&Card> for FsrsCard {
+    fn from(card: &Card) -> Self {
+        Self {
+            has_id: card.id.is_some(),
+            id: card.id.unwrap_or_default(),
+            has_preset_id: card.preset_id.is_some(),
+            preset_id: card.preset_id.unwrap_or_default(),
+            due: card.due.timestamp_millis(),
+            stability: card.stability,
+            difficulty: card.difficulty,
+            elapsed_days: card.elapsed_days,
+            scheduled_days: card.scheduled_days,
+            reps: card.reps,
+            lapses: card.lapses,
+            state: card.state as i32,
+            last_review: card.last_review.timestamp_millis(),
+            status: match card.status {
+                QueueStatus::Active => 0,
+                QueueStatus::Suspended => 1,
+                QueueStatus::Buried(_) => 2,
+            },
+            buried_until: match card.status {
+                QueueStatus::Buried(until) => until.timestamp_millis(),
+                _ => 0,
+            },
+        }
+    }
+}
+
+impl TryFrom<&FsrsCard> for Card {
+    type Error = FsrsStatus;
+
+    fn try_from(card: &FsrsCard) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: card.has_id.then_some(card.id),
+            preset_id: card.has_preset_id.then_some(card.preset_id),
+            due: to_datetime(card.due)?,
+            stability: card.stability,
+            difficulty: card.difficulty,
+            elapsed_days: card.elapsed_days,
+            scheduled_days: card.scheduled_days,
+            reps: card.reps,
+            lapses: card.lapses,
+            state: to_state(card.state)?,
+            last_review: to_datetime(card.last_review)?,
+            status: to_queue_status(card.status, card.buried_until)?,
+        })
+    }
+}
+
+impl From<&ReviewLog> for FsrsReviewLog {
+    fn from(log: &ReviewLog) -> Self {
+        Self {
+            has_card_id: log.card_id.is_some(),
+            card_id: log.card_id.unwrap_or_default(),
+            has_preset_id: log.preset_id.is_some(),
+            preset_id: log.preset_id.unwrap_or_default(),
+            rating: log.rating as i32,
+            elapsed_days: log.elapsed_days,
+            scheduled_days: log.scheduled_days,
+            state: log.state as i32,
+            reviewed_date: log.reviewed_date.timestamp_millis(),
+            review_duration: log.review_duration.map_or(-1, i64::from),
+            timing: log.timing as i32,
+        }
+    }
+}
+
+impl From<&SchedulingInfo> for FsrsSchedulingInfo {
+    fn from(info: &SchedulingInfo) -> Self {
+        Self {
+            card: (&info.card).into(),
+            review_log: (&info.review_log).into(),
+        }
+    }
+}
+
+fn to_datetime(timestamp: i64) -> Result<DateTime<Utc>, FsrsStatus> {
+    DateTime::from_timestamp_millis(timestamp).ok_or(FsrsStatus::InvalidTimestamp)
+}
+
+const fn to_state(state: i32) -> Result<State, FsrsStatus> {
+    match state {
+        0 => Ok(State::New),
+        1 => Ok(State::Learning),
+        2 => Ok(State::Review),
+        3 => Ok(State::Relearning),
+        _ => Err(FsrsStatus::InvalidState),
+    }
+}
+
+fn to_queue_status(status: i32, buried_until: i64) -> Result<QueueStatus, FsrsStatus> {
+    match status {
+        0 => Ok(QueueStatus::Active),
+        1 => Ok(QueueStatus::Suspended),
+        2 => Ok(QueueStatus::Buried(to_datetime(buried_until)?)),
+        _ => Err(FsrsStatus::InvalidQueueStatus),
+    }
+}
+
+const fn to_rating(rating: i32) -> Result<Rating, FsrsStatus> {
+    match rating {
+        1 => Ok(Rating::Again),
+        2 => Ok(Rating::Hard),
+        3 => Ok(Rating::Good),
+        4 => Ok(Rating::Easy),
+        _ => Err(FsrsStatus::InvalidRating),
+    }
+}
+
+fn guard(f: impl FnOnce() -> Result<(), FsrsStatus> + UnwindSafe) -> FsrsStatus {
+    match catch_unwind(f) {
+        Ok(Ok(())) => FsrsStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => FsrsStatus::Panic,
+    }
+}
+
+/// Layout version of the structs in this library, `FSRS_ABI_VERSION` from its header.
+#[unsafe(no_mangle)]
+pub extern "C" fn fsrs_abi_version() -> u32 {
+    FSRS_ABI_VERSION
+}
+
+/// Writes the default parameters to `out`.
+///
+/// # Safety
+///
+/// `out` must be null or point to writable memory for an `FsrsParameters`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_default_parameters(out: *mut FsrsParameters) -> FsrsStatus {
+    if out.is_null() {
+        return FsrsStatus::NullPointer;
+    }
+    guard(|| {
+        unsafe { out.write((&Parameters::default()).into()) };
+        Ok(())
+    })
+}
+
+/// Creates a scheduler, or returns null if `parameters` is null. Release it with `fsrs_free`.
+///
+/// # Safety
+///
+/// `parameters` must be null or point to a valid `FsrsParameters`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_new(parameters: *const FsrsParameters) -> *mut FSRS {
+    let Some(parameters) = (unsafe { parameters.as_ref() }) else {
+        return ptr::null_mut();
+    };
+    catch_unwind(|| Box::into_raw(Box::new(FSRS::new(parameters.into()))))
+        .unwrap_or(ptr::null_mut())
+}
+
+/// Releases a scheduler created by `fsrs_new`.
+///
+/// # Safety
+///
+/// `fsrs` must be null or a pointer returned by `fsrs_new` that was not freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_free(fsrs: *mut FSRS) {
+    if !fsrs.is_null() {
+        drop(unsafe { Box::from_raw(fsrs) });
+    }
+}
+
+/// Writes a new card due at `now` to `out`.
+///
+/// # Safety
+///
+/// `out` must be null or point to writable memory for an `FsrsCard`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_card_new(now: i64, out: *mut FsrsCard) -> FsrsStatus {
+    if out.is_null() {
+        return FsrsStatus::NullPointer;
+    }
+    guard(|| {
+        let now = to_datetime(now)?;
+        let card = Card {
+            due: now,
+            last_review: now,
+            ..Default::default()
+        };
+        unsafe { out.write((&card).into()) };
+        Ok(())
+    })
+}
+
+/// Previews the outcome of every rating for `card` reviewed at `now`.
+///
+/// # Safety
+///
+/// `fsrs` must come from `fsrs_new`, `card` must point to a valid `FsrsCard` and
+/// `out` to writable memory for an `FsrsRecordLog`; any of them may be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_repeat(
+    fsrs: *const FSRS,
+    card: *const FsrsCard,
+    now: i64,
+    out: *mut FsrsRecordLog,
+) -> FsrsStatus {
+    let (Some(fsrs), Some(card)) = (unsafe { fsrs.as_ref() }, unsafe { card.as_ref() }) else {
+        return FsrsStatus::NullPointer;
+    };
+    if out.is_null() {
+        return FsrsStatus::NullPointer;
+    }
+    guard(|| {
+        let record_log = fsrs.repeat(card.try_into()?, to_datetime(now)?);
+        let record_log = FsrsRecordLog {
+            again: (&record_log[Rating::Again]).into(),
+            hard: (&record_log[Rating::Hard]).into(),
+            good: (&record_log[Rating::Good]).into(),
+            easy: (&record_log[Rating::Easy]).into(),
+        };
+        unsafe { out.write(record_log) };
+        Ok(())
+    })
+}
+
+/// Reviews `card` at `now` with `rating` (1 = Again ... 4 = Easy).
+///
+/// # Safety
+///
+/// `fsrs` must come from `fsrs_new`, `card` must point to a valid `FsrsCard` and
+/// `out` to writable memory for an `FsrsSchedulingInfo`; any of them may be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_next(
+    fsrs: *const FSRS,
+    card: *const FsrsCard,
+    now: i64,
+    rating: i32,
+    out: *mut FsrsSchedulingInfo,
+) -> FsrsStatus {
+    let (Some(fsrs), Some(card)) = (unsafe { fsrs.as_ref() }, unsafe { card.as_ref() }) else {
+        return FsrsStatus::NullPointer;
+    };
+    if out.is_null() {
+        return FsrsStatus::NullPointer;
+    }
+    guard(|| {
+        let info = fsrs.next(card.try_into()?, to_datetime(now)?, to_rating(rating)?);
+        unsafe { out.write((&info).into()) };
+        Ok(())
+    })
+}
+
+/// Writes the probability of recalling `card` at `now` to `out`.
+///
+/// # Safety
+///
+/// `card` must point to a valid `FsrsCard` and `out` to a writable `double`; either may be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn fsrs_card_get_retrievability(
+    card: *const FsrsCard,
+    now: i64,
+    out: *mut f64,
+) -> FsrsStatus {
+    let Some(card) = (unsafe { card.as_ref() }) else {
+        return FsrsStatus::NullPointer;
+    };
+    if out.is_null() {
+        return FsrsStatus::NullPointer;
+    }
+    guard(|| {
+        let card: Card = card.try_into()?;
+        unsafe { out.write(card.get_retrievability(to_datetime(now)?)) };
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1669725000000;
+
+    #[test]
+    fn test_abi_version() {
+        assert_eq!(fsrs_abi_version(), FSRS_ABI_VERSION);
+        assert!(
+            include_str!("../include/rs_fsrs.h")
+                .contains(&format!("#define FSRS_ABI_VERSION {FSRS_ABI_VERSION}"))
+        );
+    }
+
+    fn new_fsrs() -> *mut FSRS {
+        let mut parameters = FsrsParameters::from(&Parameters::default());
+        unsafe {
+            assert_eq!(fsrs_default_parameters(&mut parameters), FsrsStatus::Ok);
+            fsrs_new(&parameters)
+        }
+    }
+
+    #[test]
+    fn test_repeat_and_next() {
+        let fsrs = new_fsrs();
+        let mut card = FsrsCard::default();
+        let mut record_log = FsrsRecordLog::default();
+        let mut info = FsrsSchedulingInfo::default();
+        unsafe {
+            assert_eq!(fsrs_card_new(NOW, &mut card), FsrsStatus::Ok);
+            assert_eq!(
+                fsrs_repeat(fsrs, &card, NOW, &mut record_log),
+                FsrsStatus::Ok
+            );
+            assert_eq!(fsrs_next(fsrs, &card, NOW, 4, &mut info), FsrsStatus::Ok);
+            fsrs_free(fsrs);
+        }
+
+        assert_eq!(record_log.again.card.state, State::Learning as i32);
+        assert_eq!(record_log.easy.card.state, State::Review as i32);
+        assert_eq!(record_log.easy.review_log.rating, Rating::Easy as i32);
+        assert_eq!(
+            info.card.scheduled_days,
+            record_log.easy.card.scheduled_days
+        );
+        assert_eq!(info.card.due, record_log.easy.card.due);
+        assert!(!info.card.has_id && !info.review_log.has_card_id);
+    }
+
+    #[test]
+    fn test_ids() {
+        let fsrs = new_fsrs();
+        let mut card = FsrsCard::default();
+        let mut info = FsrsSchedulingInfo::default();
+        unsafe {
+            fsrs_card_new(NOW, &mut card);
+            card.has_id = true;
+            card.id = 42;
+            fsrs_next(fsrs, &card, NOW, 3, &mut info);
+            fsrs_free(fsrs);
+        }
+
+        assert!(info.card.has_id && !info.card.has_preset_id);
+        assert_eq!(info.card.id, 42);
+        assert!(info.review_log.has_card_id && !info.review_log.has_preset_id);
+        assert_eq!(info.review_log.card_id, 42);
+    }
+
+    #[test]
+    fn test_retrievability() {
+        let fsrs = new_fsrs();
+        let mut card = FsrsCard::default();
+        let mut info = FsrsSchedulingInfo::default();
+        let mut retrievability = 0.0;
+        unsafe {
+            fsrs_card_new(NOW, &mut card);
+            fsrs_next(fsrs, &card, NOW, 4, &mut info);
+            let status =
+                fsrs_card_get_retrievability(&info.card, info.card.due, &mut retrievability);
+            assert_eq!(status, FsrsStatus::Ok);
+            fsrs_free(fsrs);
+        }
+
+        assert!((retrievability - 0.9026208).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_errors() {
+        let fsrs = new_fsrs();
+        let mut card = FsrsCard::default();
+        let mut info = FsrsSchedulingInfo::default();
+        unsafe {
+            fsrs_card_new(NOW, &mut card);
+            assert_eq!(
+                fsrs_next(fsrs, &card, NOW, 5, &mut info),
+                FsrsStatus::InvalidRating
+            );
+            assert_eq!(
+                fsrs_next(fsrs, ptr::null(), NOW, 3, &mut info),
+                FsrsStatus::NullPointer
+            );
+            card.state = 7;
+            assert_eq!(
+                fsrs_next(fsrs, &card, NOW, 3, &mut info),
+                FsrsStatus::InvalidState
+            );
+            card.state = 0;
+            card.status = 3;
+            assert_eq!(
+                fsrs_next(fsrs, &card, NOW, 3, &mut info),
+                FsrsStatus::InvalidQueueStatus
+            );
+            card.status = 2;
+            card.buried_until = NOW + 86_400_000;
+            assert_eq!(fsrs_next(fsrs, &card, NOW, 3, &mut info), FsrsStatus::Ok);
+            assert_eq!(
+                (info.card.status, info.card.buried_until),
+                (2, NOW + 86_400_000)
+            );
+            card.status = 0;
+            card.due = i64::MAX;
+            assert_eq!(
+                fsrs_next(fsrs, &card, NOW, 3, &mut info),
+                FsrsStatus::InvalidTimestamp
+            );
+            assert!(fsrs_new(ptr::null()).is_null());
+            fsrs_free(fsrs);
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use chrono::Duration;
 
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+
 const SECONDS_IN_A_DAY: f64 = 24.0 * 3600.0;
 
 /// Trait for working with fractional days in chrono::Duration
@@ -11,6 +14,11 @@ pub trait FractionalDays {
     fn fractional_days(fractional_days: f64) -> Self;
 }
 
+/// [`FractionalDays::fractional_days`], or `None` if the duration is out of range.
+pub fn try_fractional_days(fractional_days: f64) -> Option<Duration> {
+    Duration::try_seconds((fractional_days * SECONDS_IN_A_DAY).round() as i64)
+}
+
 impl FractionalDays for Duration {
     fn num_fractional_days(&self) -> f64 {
         self.num_seconds() as f64 / SECONDS_IN_A_DAY
@@ -0,0 +1,44 @@
+use core::fmt::Debug;
+use core::panic::{RefUnwindSafe, UnwindSafe};
+
+use crate::{Prng, ReviewSeed};
+
+/// Strategy used to pick the final interval inside the fuzz range.
+pub trait Fuzz: Debug + Send + Sync + RefUnwindSafe + UnwindSafe {
+    /// Returns a factor in `[0, 1)`; the fuzzed interval is
+    /// `min_interval + factor * (max_interval - min_interval + 1)`.
+    fn fuzz_factor(
+        &self,
+        interval: f64,
+        min_interval: i64,
+        max_interval: i64,
+        seed: &ReviewSeed,
+    ) -> f64;
+}
+
+/// Default fuzz, drawing the factor from the Alea generator seeded with the review seed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AleaFuzz;
+
+impl Fuzz for AleaFuzz {
+    fn fuzz_factor(&self, _interval: f64, _min: i64, _max: i64, seed: &ReviewSeed) -> f64 {
+        Prng::new(seed).double()
+    }
+}
+
+/// Fuzz that always keeps the un-fuzzed interval.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoFuzz;
+
+impl Fuzz for NoFuzz {
+    fn fuzz_factor(
+        &self,
+        interval: f64,
+        min_interval: i64,
+        max_interval: i64,
+        _seed: &ReviewSeed,
+    ) -> f64 {
+        let width = (max_interval - min_interval + 1) as f64;
+        ((interval - min_interval as f64) / width).clamp(0.0, 1.0 - f64::EPSILON)
+    }
+}
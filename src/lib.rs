@@ -1,10 +1,16 @@
 #![deny(warnings)]
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
 mod algo;
 pub use algo::FSRS;
 
 mod alea;
 pub use alea::{Alea, AleaState, Prng, alea};
 
+mod fuzz;
+pub use fuzz::{AleaFuzz, Fuzz, NoFuzz};
+
 mod scheduler;
 pub use scheduler::{ImplScheduler, Scheduler};
 
@@ -14,12 +20,46 @@ mod scheduler_longterm;
 pub use scheduler_longterm::LongtermScheduler;
 
 mod models;
-pub use models::{Card, Rating, RecordLog, ReviewLog, SchedulingInfo, State};
+pub use models::{Card, QueueStatus, Rating, ReviewLog, ReviewTiming, SchedulingInfo, State};
+
+mod queue;
+pub use queue::due_cards;
+
+mod record_log;
+pub use record_log::RecordLog;
 
 mod parameters;
-pub use crate::parameters::{Parameters, Seed};
+pub use crate::parameters::{
+    FuzzRange, IntervalInfo, LeechAction, Parameters, ReviewSeed, Seed, SeedPolicy,
+};
+
+mod presets;
+pub use presets::PresetRegistry;
+
+mod siblings;
+pub use siblings::bury_siblings;
+#[cfg(feature = "std")]
+pub use siblings::group_siblings;
+
+mod explain;
+pub use explain::{Explanation, RecallTerms, StabilityUpdate};
+
+pub mod stats;
 
 mod fractional_days;
 pub use fractional_days::FractionalDays;
 
+#[cfg(not(feature = "std"))]
+mod math;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "python")]
+pub mod python;
+
+#[cfg(feature = "std")]
 mod tests;
@@ -0,0 +1,41 @@
+/// `f64` methods that live in `std`, implemented with `libm` for `no_std` builds.
+pub trait Float {
+    fn floor(self) -> Self;
+    fn round(self) -> Self;
+    fn exp(self) -> Self;
+    fn exp_m1(self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+impl Float for f64 {
+    fn floor(self) -> Self {
+        libm::floor(self)
+    }
+
+    fn round(self) -> Self {
+        libm::round(self)
+    }
+
+    fn exp(self) -> Self {
+        libm::exp(self)
+    }
+
+    fn exp_m1(self) -> Self {
+        libm::expm1(self)
+    }
+
+    fn powf(self, n: Self) -> Self {
+        libm::pow(self, n)
+    }
+
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        libm::fma(self, a, b)
+    }
+
+    fn rem_euclid(self, rhs: Self) -> Self {
+        let r = libm::fmod(self, rhs);
+        if r < 0.0 { r + libm::fabs(rhs) } else { r }
+    }
+}
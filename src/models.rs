@@ -1,11 +1,19 @@
-use crate::Parameters;
+use crate::{IntervalInfo, LeechAction, Parameters};
 use chrono::{DateTime, Utc};
+#[cfg(feature = "python")]
+use pyo3::pyclass;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::wasm_bindgen;
 
-#[derive(Clone, Copy, PartialEq, Debug, Default, Eq)]
+#[derive(Clone, Copy, PartialEq, Debug, Default, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "rs_fsrs", eq, eq_int, hash, frozen, from_py_object)
+)]
 pub enum State {
     #[default]
     New = 0,
@@ -14,8 +22,73 @@ pub enum State {
     Relearning = 3,
 }
 
+/// When a card was reviewed relative to its scheduled interval, in whole days.
+///
+/// Reviews within a quarter of the interval, and at least a day, of the due
+/// date count as on time, so fuzz and next-day learning reviews are `OnTime`.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "rs_fsrs", eq, eq_int, hash, frozen, from_py_object)
+)]
+pub enum ReviewTiming {
+    /// Reviewed more than the tolerance before the due date
+    Early = 0,
+    #[default]
+    OnTime = 1,
+    /// Reviewed more than the tolerance after the due date
+    Late = 2,
+}
+
+impl ReviewTiming {
+    pub const fn new(elapsed_days: i64, scheduled_days: i64) -> Self {
+        let tolerance = if scheduled_days / 4 > 1 {
+            scheduled_days / 4
+        } else {
+            1
+        };
+        if elapsed_days < scheduled_days - tolerance {
+            Self::Early
+        } else if elapsed_days > scheduled_days + tolerance {
+            Self::Late
+        } else {
+            Self::OnTime
+        }
+    }
+}
+
+/// Whether a card is shown in the review queue, independent of its memory [`State`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum QueueStatus {
+    #[default]
+    Active,
+    /// Left out of the queue until unsuspended
+    Suspended,
+    /// Left out of the queue until the given time
+    Buried(DateTime<Utc>),
+}
+
+impl QueueStatus {
+    /// Whether the card can be shown at `now`; buried cards come back when the burial ends.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Active => true,
+            Self::Suspended => false,
+            Self::Buried(until) => *until <= now,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "rs_fsrs", eq, eq_int, hash, frozen, from_py_object)
+)]
 pub enum Rating {
     Again = 1,
     Hard = 2,
@@ -24,33 +97,55 @@ pub enum Rating {
 }
 
 impl Rating {
-    pub fn iter() -> std::slice::Iter<'static, Self> {
+    pub fn iter() -> core::slice::Iter<'static, Self> {
         static VARIANTS: [Rating; 4] = [Rating::Again, Rating::Hard, Rating::Good, Rating::Easy];
         VARIANTS.iter()
     }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SchedulingInfo {
     pub card: Card,
     pub review_log: ReviewLog,
+    /// How the interval was chosen, `None` for learning steps
+    pub interval: Option<IntervalInfo>,
+    /// Action taken because this review made the card a leech
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub leech: Option<LeechAction>,
 }
 
-pub type RecordLog = HashMap<Rating, SchedulingInfo>;
-
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReviewLog {
+    /// `Card::id` of the reviewed card
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub card_id: Option<i64>,
+    /// `Card::preset_id` of the reviewed card
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub preset_id: Option<i64>,
     pub rating: Rating,
     pub elapsed_days: i64,
     pub scheduled_days: i64,
     pub state: State,
     pub reviewed_date: DateTime<Utc>,
+    /// Time taken to answer, in milliseconds
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub review_duration: Option<u32>,
+    /// Whether the card was reviewed before, on or after its due day
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub timing: ReviewTiming,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Card {
+    /// Id of the card in your collection
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub id: Option<i64>,
+    /// Preset (deck options) the card is scheduled with, `None` for the default preset
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub preset_id: Option<i64>,
     pub due: DateTime<Utc>,
     pub stability: f64,
     pub difficulty: f64,
@@ -60,17 +155,35 @@ pub struct Card {
     pub lapses: i32,
     pub state: State,
     pub last_review: DateTime<Utc>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status: QueueStatus,
 }
 
 impl Card {
+    #[cfg(feature = "std")]
     pub fn new() -> Self {
+        Self::new_at(Utc::now())
+    }
+
+    /// A new card due at `now`.
+    pub fn new_at(now: DateTime<Utc>) -> Self {
         Self {
-            due: Utc::now(),
-            last_review: Utc::now(),
+            due: now,
+            last_review: now,
             ..Default::default()
         }
     }
 
+    /// Whether the card is due at `now` and neither suspended nor buried.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.due <= now && self.status.is_active(now)
+    }
+
+    /// Retrievability at `now`, counting whole days since the last review like the
+    /// schedulers do, on the default forgetting curve. [`FSRS::retrievability_at`]
+    /// counts fractional days and uses the scheduler's `decay` and `factor`.
+    ///
+    /// [`FSRS::retrievability_at`]: crate::FSRS::retrievability_at
     pub fn get_retrievability(&self, now: DateTime<Utc>) -> f64 {
         match self.state {
             State::New => 0.0,
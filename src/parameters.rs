@@ -1,7 +1,13 @@
-use chrono::Utc;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::Rating;
-use crate::alea;
+use crate::fuzz::{AleaFuzz, Fuzz};
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
 
 type Weights = [f64; 19];
 const DEFAULT_WEIGHTS: Weights = [
@@ -10,6 +16,7 @@ const DEFAULT_WEIGHTS: Weights = [
 ];
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Parameters {
     pub request_retention: f64,
     pub maximum_interval: i32,
@@ -18,7 +25,22 @@ pub struct Parameters {
     pub factor: f64,
     pub enable_short_term: bool,
     pub enable_fuzz: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub fuzz: Arc<dyn Fuzz>,
     pub seed: Seed,
+    pub seed_policy: SeedPolicy,
+    /// Minimum number of days between a card and its siblings
+    pub sibling_spacing: i64,
+    /// Lapses at which a card becomes a leech, 0 to never mark leeches
+    pub leech_threshold: i32,
+    pub leech_action: LeechAction,
+    /// With `Some(w19)`, same-day reviews of reviewed cards get the FSRS-6 same-day
+    /// stability, whose growth shrinks as stability rises (FSRS-6 defaults to 0.1542);
+    /// `None` keeps the long-term stability growth
+    pub early_review_damping: Option<f64>,
+    /// Largest elapsed time, in multiples of the scheduled interval, that counts
+    /// towards the retrievability of an overdue card; `None` counts all of it
+    pub overdue_cap: Option<f64>,
 }
 
 impl Parameters {
@@ -26,10 +48,29 @@ impl Parameters {
     /// (9/10) ^ (1 / DECAY) - 1
     pub const FACTOR: f64 = 19f64 / 81f64;
 
+    /// Retrievability with the default `DECAY` and `FACTOR`; the schedulers use
+    /// [`Parameters::retrievability`] so that a custom `decay` and `factor` apply.
     pub fn forgetting_curve(elapsed_days: f64, stability: f64) -> f64 {
         (1.0 + Self::FACTOR * elapsed_days / stability).powf(Self::DECAY)
     }
 
+    /// Retrievability `elapsed_days` after the last review, with this instance's `decay` and `factor`.
+    pub fn retrievability(&self, elapsed_days: f64, stability: f64) -> f64 {
+        (1.0 + self.factor * elapsed_days / stability).powf(self.decay)
+    }
+
+    /// Days after the last review until retrievability drops to `retrievability`.
+    pub fn elapsed_days_for_retrievability(&self, stability: f64, retrievability: f64) -> f64 {
+        stability / self.factor * (retrievability.powf(1.0 / self.decay) - 1.0)
+    }
+
+    /// Un-fuzzed interval that would be scheduled if `request_retention` were `retention`.
+    pub fn interval_for_retention(&self, stability: f64, retention: f64) -> f64 {
+        self.elapsed_days_for_retrievability(stability, retention)
+            .round()
+            .clamp(1.0, self.maximum_interval as f64)
+    }
+
     pub fn init_difficulty(&self, rating: Rating) -> f64 {
         let rating_int: i32 = rating as i32;
 
@@ -41,13 +82,62 @@ impl Parameters {
         self.w[(rating_int - 1) as usize].max(0.1)
     }
 
-    #[allow(clippy::suboptimal_flops)]
     pub fn next_interval(&self, stability: f64, elapsed_days: i64) -> f64 {
-        let new_interval = (stability / Self::FACTOR
-            * (self.request_retention.powf(1.0 / Self::DECAY) - 1.0))
-            .round()
-            .clamp(1.0, self.maximum_interval as f64);
-        self.apply_fuzz(new_interval, elapsed_days)
+        self.next_interval_info(stability, elapsed_days).interval
+    }
+
+    /// Interval fuzzed with `Parameters::seed`.
+    pub fn next_interval_info(&self, stability: f64, elapsed_days: i64) -> IntervalInfo {
+        self.next_interval_info_with_seed(stability, elapsed_days, &ReviewSeed::Fixed(&self.seed))
+    }
+
+    pub fn next_interval_info_with_seed(
+        &self,
+        stability: f64,
+        elapsed_days: i64,
+        seed: &ReviewSeed,
+    ) -> IntervalInfo {
+        self.next_interval_info_with_retention(
+            stability,
+            elapsed_days,
+            seed,
+            self.request_retention,
+        )
+    }
+
+    /// Interval reaching `request_retention` instead of `Parameters::request_retention`.
+    pub fn next_interval_info_with_retention(
+        &self,
+        stability: f64,
+        elapsed_days: i64,
+        seed: &ReviewSeed,
+        request_retention: f64,
+    ) -> IntervalInfo {
+        let new_interval = self.interval_for_retention(stability, request_retention);
+        self.apply_fuzz(new_interval, elapsed_days, seed)
+    }
+
+    /// Interval reaching `request_retention`, before rounding and clamping to `maximum_interval`.
+    pub fn ideal_interval(&self, stability: f64) -> f64 {
+        self.elapsed_days_for_retrievability(stability, self.request_retention)
+    }
+
+    /// Whether a lapse bringing a card to `lapses` makes it a leech: at
+    /// `leech_threshold` and every half threshold after that.
+    pub const fn is_leech(&self, lapses: i32) -> bool {
+        if self.leech_threshold <= 0 || lapses < self.leech_threshold {
+            return false;
+        }
+        let every = if self.leech_threshold > 1 {
+            self.leech_threshold / 2
+        } else {
+            1
+        };
+        (lapses - self.leech_threshold) % every == 0
+    }
+
+    pub fn get_fuzz_range(&self, interval: f64, elapsed_days: i64) -> (i64, i64) {
+        FuzzRange::get_fuzz_range(interval, elapsed_days, self.maximum_interval)
     }
 
     pub fn next_difficulty(&self, difficulty: f64, rating: Rating) -> f64 {
@@ -63,6 +153,16 @@ impl Parameters {
         stability * f64::exp(self.w[17] * (rating_int as f64 - 3.0 + self.w[18]))
     }
 
+    /// FSRS-6 same-day stability: the short-term growth times `S^-damping`, at
+    /// least 1 for `Good` and `Easy`.
+    pub fn same_day_stability(&self, stability: f64, rating: Rating, damping: f64) -> f64 {
+        let mut growth = self.short_term_stability(1.0, rating) * stability.powf(-damping);
+        if matches!(rating, Rating::Good | Rating::Easy) {
+            growth = growth.max(1.0);
+        }
+        stability * growth
+    }
+
     pub fn next_recall_stability(
         &self,
         difficulty: f64,
@@ -100,20 +200,32 @@ impl Parameters {
         self.w[7].mul_add(initial, (1.0 - self.w[7]) * current)
     }
 
-    fn apply_fuzz(&self, interval: f64, elapsed_days: i64) -> f64 {
-        if !self.enable_fuzz || interval < 2.5 {
-            return interval;
+    fn apply_fuzz(&self, interval: f64, elapsed_days: i64, seed: &ReviewSeed) -> IntervalInfo {
+        let mut info = IntervalInfo {
+            raw_interval: interval,
+            min_interval: interval as i64,
+            max_interval: interval as i64,
+            fuzz_factor: None,
+            interval,
+        };
+        if interval < 2.5 {
+            return info;
         }
 
-        let mut generator = alea(self.seed.clone());
-        let fuzz_factor = generator.double();
-        let (min_interval, max_interval) =
-            FuzzRange::get_fuzz_range(interval, elapsed_days, self.maximum_interval);
+        (info.min_interval, info.max_interval) = self.get_fuzz_range(interval, elapsed_days);
+        if !self.enable_fuzz {
+            return info;
+        }
 
-        fuzz_factor.mul_add(
-            max_interval as f64 - min_interval as f64 + 1.0,
-            min_interval as f64,
-        )
+        let fuzz_factor =
+            self.fuzz
+                .fuzz_factor(interval, info.min_interval, info.max_interval, seed);
+        info.fuzz_factor = Some(fuzz_factor);
+        info.interval = fuzz_factor.mul_add(
+            info.max_interval as f64 - info.min_interval as f64 + 1.0,
+            info.min_interval as f64,
+        );
+        info
     }
 }
 
@@ -127,12 +239,37 @@ impl Default for Parameters {
             factor: Self::FACTOR,
             enable_short_term: true,
             enable_fuzz: false,
+            fuzz: Arc::new(AleaFuzz),
             seed: Seed::default(),
+            seed_policy: SeedPolicy::default(),
+            sibling_spacing: 1,
+            leech_threshold: 8,
+            leech_action: LeechAction::default(),
+            early_review_damping: None,
+            overdue_cap: None,
         }
     }
 }
 
-struct FuzzRange {
+/// How the interval scheduled for a rating was derived.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct IntervalInfo {
+    /// Interval computed from stability and `request_retention`, before fuzzing
+    pub raw_interval: f64,
+    /// Lower bound of the fuzz range
+    pub min_interval: i64,
+    /// Upper bound of the fuzz range
+    pub max_interval: i64,
+    /// Random factor in `[0, 1)` used to pick a day in the fuzz range, if fuzz was applied
+    pub fuzz_factor: Option<f64>,
+    /// Interval scheduled for the card, after fuzzing and after the ratings were put
+    /// in order; pushing Hard, Good and Easy apart may move it outside the fuzz range
+    pub interval: f64,
+}
+
+pub struct FuzzRange {
     start: f64,
     end: f64,
     factor: f64,
@@ -143,7 +280,8 @@ impl FuzzRange {
         Self { start, end, factor }
     }
 
-    fn get_fuzz_range(interval: f64, elapsed_days: i64, maximum_interval: i32) -> (i64, i64) {
+    /// Returns the inclusive `(min, max)` range of days an interval may be fuzzed to.
+    pub fn get_fuzz_range(interval: f64, elapsed_days: i64, maximum_interval: i32) -> (i64, i64) {
         let mut delta: f64 = 1.0;
         for fuzz_range in FUZZ_RANGE {
             delta += fuzz_range.factor
@@ -170,7 +308,60 @@ const FUZZ_RANGE: [FuzzRange; 3] = [
     FuzzRange::new(20.0, f64::MAX, 0.05),
 ];
 
+/// What happens to a card when a lapse makes it a leech.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum LeechAction {
+    /// Only report the leech in `SchedulingInfo::leech`
+    #[default]
+    Tag,
+    /// Suspend the card
+    Suspend,
+    /// Forget the card: it starts over as a new card due at the review
+    Reset,
+}
+
+/// How the fuzz seed of a review is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub enum SeedPolicy {
+    /// Derived from the review time, reps and memory state of the card
+    #[default]
+    Card,
+    /// Always `Parameters::seed`
+    Fixed,
+    /// Derived from `Card::id` and reps, so every server fuzzes a review the same way.
+    /// Cards without an id are seeded as with [`SeedPolicy::Card`].
+    CardIdAndReps,
+}
+
+/// Fuzz seed of a single review, chosen by the [`SeedPolicy`]. It is only
+/// formatted while seeding, so reviews don't allocate a seed string.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum ReviewSeed<'a> {
+    /// Formatted as `"{time}_{reps}_{difficulty * stability}"`, `time` in milliseconds
+    Card { time: i64, reps: i32, mul: f64 },
+    /// `Parameters::seed`
+    Fixed(&'a Seed),
+    /// Formatted as `"{id}_{reps}"`
+    CardIdAndReps { id: i64, reps: i32 },
+}
+
+impl fmt::Display for ReviewSeed<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Card { time, reps, mul } => write!(f, "{time}_{reps}_{mul}"),
+            Self::Fixed(seed) => write!(f, "{seed}"),
+            Self::CardIdAndReps { id, reps } => write!(f, "{id}_{reps}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Seed {
     String(String),
     Empty,
@@ -180,7 +371,7 @@ pub enum Seed {
 impl Seed {
     pub fn new<T>(value: T) -> Self
     where
-        T: std::fmt::Display,
+        T: fmt::Display,
     {
         if value.to_string().is_empty() {
             Self::default()
@@ -192,14 +383,13 @@ impl Seed {
     pub fn inner_str(&self) -> &str {
         match self {
             Self::String(str) => str,
-            Self::Empty => Self::Default.inner_str(),
-            Self::Default => Self::Default.inner_str(),
+            Self::Empty | Self::Default => "",
         }
     }
 }
 
-impl std::fmt::Display for Seed {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl fmt::Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.inner_str())
     }
 }
@@ -228,8 +418,15 @@ impl<'a> From<&'a str> for Seed {
     }
 }
 
+/// The current time in milliseconds, or [`Seed::Empty`] without `std`.
 impl Default for Seed {
+    #[cfg(feature = "std")]
+    fn default() -> Self {
+        Self::String(chrono::Utc::now().timestamp_millis().to_string())
+    }
+
+    #[cfg(not(feature = "std"))]
     fn default() -> Self {
-        Self::String(Utc::now().timestamp_millis().to_string())
+        Self::Empty
     }
 }
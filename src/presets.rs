@@ -0,0 +1,89 @@
+use alloc::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+
+use crate::{Card, FSRS, Parameters, Rating, RecordLog, ReviewLog, SchedulingInfo};
+
+/// Parameters for every preset of a collection, looked up by `Card::preset_id`.
+/// Ids without a preset of their own use the default preset.
+#[derive(Debug, Default, Clone)]
+pub struct PresetRegistry {
+    default: FSRS,
+    presets: BTreeMap<i64, FSRS>,
+}
+
+impl PresetRegistry {
+    pub const fn new(default: Parameters) -> Self {
+        Self {
+            default: FSRS::new(default),
+            presets: BTreeMap::new(),
+        }
+    }
+
+    /// Add or replace a preset, returning the scheduler it replaced.
+    pub fn insert(&mut self, preset_id: i64, parameters: Parameters) -> Option<FSRS> {
+        self.presets.insert(preset_id, FSRS::new(parameters))
+    }
+
+    pub fn remove(&mut self, preset_id: i64) -> Option<FSRS> {
+        self.presets.remove(&preset_id)
+    }
+
+    pub fn contains(&self, preset_id: i64) -> bool {
+        self.presets.contains_key(&preset_id)
+    }
+
+    /// Presets other than the default, by id.
+    pub fn iter(&self) -> impl Iterator<Item = (i64, &FSRS)> {
+        self.presets
+            .iter()
+            .map(|(&preset_id, fsrs)| (preset_id, fsrs))
+    }
+
+    pub const fn default_preset(&self) -> &FSRS {
+        &self.default
+    }
+
+    /// Scheduler for `preset_id`, falling back to the default preset.
+    pub fn get(&self, preset_id: i64) -> &FSRS {
+        self.presets.get(&preset_id).unwrap_or(&self.default)
+    }
+
+    /// Scheduler for `Card::preset_id`, the default preset for `None`.
+    fn preset(&self, preset_id: Option<i64>) -> &FSRS {
+        preset_id.map_or(&self.default, |preset_id| self.get(preset_id))
+    }
+
+    /// [`FSRS::repeat`] with the preset of `card`.
+    pub fn repeat(&self, card: Card, now: DateTime<Utc>) -> RecordLog {
+        self.preset(card.preset_id).repeat(card, now)
+    }
+
+    /// [`FSRS::next`] with the preset of `card`.
+    pub fn next(&self, card: Card, now: DateTime<Utc>, rating: Rating) -> SchedulingInfo {
+        self.preset(card.preset_id).next(card, now, rating)
+    }
+
+    /// Move `card` to `preset_id` (`None` for the default preset), recomputing its
+    /// memory state and due date by replaying `logs` with the new preset. The queue
+    /// status of `card` is kept. Without logs only the preset id changes.
+    pub fn migrate<'a>(
+        &self,
+        card: Card,
+        preset_id: Option<i64>,
+        logs: impl IntoIterator<Item = &'a ReviewLog>,
+    ) -> Card {
+        let mut logs = logs.into_iter().peekable();
+        let Some(first) = logs.peek() else {
+            return Card { preset_id, ..card };
+        };
+        let new = Card {
+            id: card.id,
+            preset_id,
+            ..Card::new_at(first.reviewed_date)
+        };
+        Card {
+            status: card.status,
+            ..self.preset(preset_id).replay(new, logs)
+        }
+    }
+}
@@ -0,0 +1,724 @@
+//! Python bindings built with PyO3, packaged with `maturin` as the `rs_fsrs` module.
+//!
+//! Timestamps are timezone-aware `datetime.datetime` objects and every class
+//! can be pickled.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::{
+    Card, FSRS, Parameters, QueueStatus, Rating, ReviewLog, ReviewTiming, SchedulingInfo, State,
+};
+
+#[pymethods]
+impl Rating {
+    #[new]
+    fn py_new(value: i32) -> PyResult<Self> {
+        Self::iter()
+            .find(|&&rating| rating as i32 == value)
+            .copied()
+            .ok_or_else(|| PyValueError::new_err(format!("invalid rating: {value}")))
+    }
+
+    fn __getnewargs__(&self) -> (i32,) {
+        (*self as i32,)
+    }
+}
+
+#[pymethods]
+impl State {
+    #[new]
+    fn py_new(value: i32) -> PyResult<Self> {
+        match value {
+            0 => Ok(Self::New),
+            1 => Ok(Self::Learning),
+            2 => Ok(Self::Review),
+            3 => Ok(Self::Relearning),
+            _ => Err(PyValueError::new_err(format!("invalid state: {value}"))),
+        }
+    }
+
+    fn __getnewargs__(&self) -> (i32,) {
+        (*self as i32,)
+    }
+}
+
+#[pymethods]
+impl ReviewTiming {
+    #[new]
+    fn py_new(value: i32) -> PyResult<Self> {
+        match value {
+            0 => Ok(Self::Early),
+            1 => Ok(Self::OnTime),
+            2 => Ok(Self::Late),
+            _ => Err(PyValueError::new_err(format!(
+                "invalid review timing: {value}"
+            ))),
+        }
+    }
+
+    fn __getnewargs__(&self) -> (i32,) {
+        (*self as i32,)
+    }
+}
+
+#[pyclass(name = "Parameters", module = "rs_fsrs", from_py_object)]
+#[derive(Debug, Clone, Default)]
+pub struct PyParameters {
+    inner: Parameters,
+}
+
+#[pymethods]
+impl PyParameters {
+    #[new]
+    #[pyo3(signature = (
+        request_retention = 0.9,
+        maximum_interval = 36500,
+        w = None,
+        enable_short_term = true,
+        enable_fuzz = false,
+    ))]
+    fn new(
+        request_retention: f64,
+        maximum_interval: i32,
+        w: Option<[f64; 19]>,
+        enable_short_term: bool,
+        enable_fuzz: bool,
+    ) -> Self {
+        let default = Parameters::default();
+        Self {
+            inner: Parameters {
+                request_retention,
+                maximum_interval,
+                w: w.unwrap_or(default.w),
+                enable_short_term,
+                enable_fuzz,
+                ..default
+            },
+        }
+    }
+
+    #[getter]
+    fn request_retention(&self) -> f64 {
+        self.inner.request_retention
+    }
+
+    #[setter]
+    fn set_request_retention(&mut self, request_retention: f64) {
+        self.inner.request_retention = request_retention;
+    }
+
+    #[getter]
+    fn maximum_interval(&self) -> i32 {
+        self.inner.maximum_interval
+    }
+
+    #[setter]
+    fn set_maximum_interval(&mut self, maximum_interval: i32) {
+        self.inner.maximum_interval = maximum_interval;
+    }
+
+    #[getter]
+    fn w(&self) -> [f64; 19] {
+        self.inner.w
+    }
+
+    #[setter]
+    fn set_w(&mut self, w: [f64; 19]) {
+        self.inner.w = w;
+    }
+
+    #[getter]
+    fn enable_short_term(&self) -> bool {
+        self.inner.enable_short_term
+    }
+
+    #[setter]
+    fn set_enable_short_term(&mut self, enable_short_term: bool) {
+        self.inner.enable_short_term = enable_short_term;
+    }
+
+    #[getter]
+    fn enable_fuzz(&self) -> bool {
+        self.inner.enable_fuzz
+    }
+
+    #[setter]
+    fn set_enable_fuzz(&mut self, enable_fuzz: bool) {
+        self.inner.enable_fuzz = enable_fuzz;
+    }
+
+    fn __getnewargs__(&self) -> (f64, i32, [f64; 19], bool, bool) {
+        (
+            self.inner.request_retention,
+            self.inner.maximum_interval,
+            self.inner.w,
+            self.inner.enable_short_term,
+            self.inner.enable_fuzz,
+        )
+    }
+}
+
+type CardState = (
+    Option<i64>,
+    Option<i64>,
+    DateTime<Utc>,
+    f64,
+    f64,
+    i64,
+    i64,
+    i32,
+    i32,
+    State,
+    DateTime<Utc>,
+    (bool, Option<DateTime<Utc>>),
+);
+
+#[pyclass(name = "Card", module = "rs_fsrs", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyCard {
+    inner: Card,
+}
+
+#[pymethods]
+impl PyCard {
+    /// Creates a new card due at `now`, or at the current time.
+    #[new]
+    #[pyo3(signature = (now = None))]
+    fn new(now: Option<DateTime<Utc>>) -> Self {
+        let now = now.unwrap_or_else(Utc::now);
+        Self {
+            inner: Card {
+                due: now,
+                last_review: now,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[getter]
+    fn id(&self) -> Option<i64> {
+        self.inner.id
+    }
+
+    #[setter]
+    fn set_id(&mut self, id: Option<i64>) {
+        self.inner.id = id;
+    }
+
+    #[getter]
+    fn preset_id(&self) -> Option<i64> {
+        self.inner.preset_id
+    }
+
+    #[setter]
+    fn set_preset_id(&mut self, preset_id: Option<i64>) {
+        self.inner.preset_id = preset_id;
+    }
+
+    #[getter]
+    fn due(&self) -> DateTime<Utc> {
+        self.inner.due
+    }
+
+    #[setter]
+    fn set_due(&mut self, due: DateTime<Utc>) {
+        self.inner.due = due;
+    }
+
+    #[getter]
+    fn stability(&self) -> f64 {
+        self.inner.stability
+    }
+
+    #[setter]
+    fn set_stability(&mut self, stability: f64) {
+        self.inner.stability = stability;
+    }
+
+    #[getter]
+    fn difficulty(&self) -> f64 {
+        self.inner.difficulty
+    }
+
+    #[setter]
+    fn set_difficulty(&mut self, difficulty: f64) {
+        self.inner.difficulty = difficulty;
+    }
+
+    #[getter]
+    fn elapsed_days(&self) -> i64 {
+        self.inner.elapsed_days
+    }
+
+    #[setter]
+    fn set_elapsed_days(&mut self, elapsed_days: i64) {
+        self.inner.elapsed_days = elapsed_days;
+    }
+
+    #[getter]
+    fn scheduled_days(&self) -> i64 {
+        self.inner.scheduled_days
+    }
+
+    #[setter]
+    fn set_scheduled_days(&mut self, scheduled_days: i64) {
+        self.inner.scheduled_days = scheduled_days;
+    }
+
+    #[getter]
+    fn reps(&self) -> i32 {
+        self.inner.reps
+    }
+
+    #[setter]
+    fn set_reps(&mut self, reps: i32) {
+        self.inner.reps = reps;
+    }
+
+    #[getter]
+    fn lapses(&self) -> i32 {
+        self.inner.lapses
+    }
+
+    #[setter]
+    fn set_lapses(&mut self, lapses: i32) {
+        self.inner.lapses = lapses;
+    }
+
+    #[getter]
+    fn state(&self) -> State {
+        self.inner.state
+    }
+
+    #[setter]
+    fn set_state(&mut self, state: State) {
+        self.inner.state = state;
+    }
+
+    #[getter]
+    fn last_review(&self) -> DateTime<Utc> {
+        self.inner.last_review
+    }
+
+    #[setter]
+    fn set_last_review(&mut self, last_review: DateTime<Utc>) {
+        self.inner.last_review = last_review;
+    }
+
+    #[getter]
+    fn suspended(&self) -> bool {
+        self.inner.status == QueueStatus::Suspended
+    }
+
+    /// Suspends the card, or makes a suspended card active again.
+    #[setter]
+    fn set_suspended(&mut self, suspended: bool) {
+        if suspended {
+            self.inner.status = QueueStatus::Suspended;
+        } else if self.inner.status == QueueStatus::Suspended {
+            self.inner.status = QueueStatus::Active;
+        }
+    }
+
+    #[getter]
+    fn buried_until(&self) -> Option<DateTime<Utc>> {
+        match self.inner.status {
+            QueueStatus::Buried(until) => Some(until),
+            _ => None,
+        }
+    }
+
+    /// Buries the card until `until`, or unburies it.
+    #[setter]
+    fn set_buried_until(&mut self, until: Option<DateTime<Utc>>) {
+        match until {
+            Some(until) => self.inner.status = QueueStatus::Buried(until),
+            None if matches!(self.inner.status, QueueStatus::Buried(_)) => {
+                self.inner.status = QueueStatus::Active;
+            }
+            None => {}
+        }
+    }
+
+    fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.inner.is_due(now)
+    }
+
+    fn get_retrievability(&self, now: DateTime<Utc>) -> f64 {
+        self.inner.get_retrievability(now)
+    }
+
+    fn __getstate__(&self) -> CardState {
+        let card = &self.inner;
+        (
+            card.id,
+            card.preset_id,
+            card.due,
+            card.stability,
+            card.difficulty,
+            card.elapsed_days,
+            card.scheduled_days,
+            card.reps,
+            card.lapses,
+            card.state,
+            card.last_review,
+            (self.suspended(), self.buried_until()),
+        )
+    }
+
+    fn __setstate__(&mut self, state: CardState) {
+        let (
+            id,
+            preset_id,
+            due,
+            stability,
+            difficulty,
+            elapsed_days,
+            scheduled_days,
+            reps,
+            lapses,
+            state,
+            last_review,
+            (suspended, buried_until),
+        ) = state;
+        self.inner = Card {
+            id,
+            preset_id,
+            due,
+            stability,
+            difficulty,
+            elapsed_days,
+            scheduled_days,
+            reps,
+            lapses,
+            state,
+            last_review,
+            status: match (suspended, buried_until) {
+                (true, _) => QueueStatus::Suspended,
+                (false, Some(until)) => QueueStatus::Buried(until),
+                (false, None) => QueueStatus::Active,
+            },
+        };
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+}
+
+#[pyclass(name = "ReviewLog", module = "rs_fsrs", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyReviewLog {
+    inner: ReviewLog,
+}
+
+#[pymethods]
+impl PyReviewLog {
+    #[new]
+    #[pyo3(signature = (
+        rating,
+        elapsed_days,
+        scheduled_days,
+        state,
+        reviewed_date,
+        review_duration=None,
+        card_id=None,
+        preset_id=None,
+        timing=ReviewTiming::OnTime,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        rating: Rating,
+        elapsed_days: i64,
+        scheduled_days: i64,
+        state: State,
+        reviewed_date: DateTime<Utc>,
+        review_duration: Option<u32>,
+        card_id: Option<i64>,
+        preset_id: Option<i64>,
+        timing: ReviewTiming,
+    ) -> Self {
+        Self {
+            inner: ReviewLog {
+                card_id,
+                preset_id,
+                rating,
+                elapsed_days,
+                scheduled_days,
+                state,
+                reviewed_date,
+                review_duration,
+                timing,
+            },
+        }
+    }
+
+    #[getter]
+    fn card_id(&self) -> Option<i64> {
+        self.inner.card_id
+    }
+
+    #[getter]
+    fn preset_id(&self) -> Option<i64> {
+        self.inner.preset_id
+    }
+
+    #[getter]
+    fn rating(&self) -> Rating {
+        self.inner.rating
+    }
+
+    #[getter]
+    fn elapsed_days(&self) -> i64 {
+        self.inner.elapsed_days
+    }
+
+    #[getter]
+    fn scheduled_days(&self) -> i64 {
+        self.inner.scheduled_days
+    }
+
+    #[getter]
+    fn state(&self) -> State {
+        self.inner.state
+    }
+
+    #[getter]
+    fn reviewed_date(&self) -> DateTime<Utc> {
+        self.inner.reviewed_date
+    }
+
+    #[getter]
+    fn review_duration(&self) -> Option<u32> {
+        self.inner.review_duration
+    }
+
+    #[getter]
+    fn timing(&self) -> ReviewTiming {
+        self.inner.timing
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn __getnewargs__(
+        &self,
+    ) -> (
+        Rating,
+        i64,
+        i64,
+        State,
+        DateTime<Utc>,
+        Option<u32>,
+        Option<i64>,
+        Option<i64>,
+        ReviewTiming,
+    ) {
+        let log = &self.inner;
+        (
+            log.rating,
+            log.elapsed_days,
+            log.scheduled_days,
+            log.state,
+            log.reviewed_date,
+            log.review_duration,
+            log.card_id,
+            log.preset_id,
+            log.timing,
+        )
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.inner)
+    }
+}
+
+#[pyclass(name = "SchedulingInfo", module = "rs_fsrs", from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PySchedulingInfo {
+    card: PyCard,
+    review_log: PyReviewLog,
+}
+
+impl From<SchedulingInfo> for PySchedulingInfo {
+    fn from(info: SchedulingInfo) -> Self {
+        Self {
+            card: PyCard { inner: info.card },
+            review_log: PyReviewLog {
+                inner: info.review_log,
+            },
+        }
+    }
+}
+
+#[pymethods]
+impl PySchedulingInfo {
+    #[new]
+    fn new(card: PyCard, review_log: PyReviewLog) -> Self {
+        Self { card, review_log }
+    }
+
+    #[getter]
+    fn card(&self) -> PyCard {
+        self.card.clone()
+    }
+
+    #[getter]
+    fn review_log(&self) -> PyReviewLog {
+        self.review_log.clone()
+    }
+
+    fn __getnewargs__(&self) -> (PyCard, PyReviewLog) {
+        (self.card.clone(), self.review_log.clone())
+    }
+}
+
+#[pyclass(name = "FSRS", module = "rs_fsrs", frozen, skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyFSRS {
+    parameters: PyParameters,
+    inner: FSRS,
+}
+
+#[pymethods]
+impl PyFSRS {
+    #[new]
+    #[pyo3(signature = (parameters = None))]
+    fn new(parameters: Option<PyParameters>) -> Self {
+        let parameters = parameters.unwrap_or_default();
+        Self {
+            inner: FSRS::new(parameters.inner.clone()),
+            parameters,
+        }
+    }
+
+    #[getter]
+    fn parameters(&self) -> PyParameters {
+        self.parameters.clone()
+    }
+
+    /// Returns a dict mapping every `Rating` to its `SchedulingInfo`.
+    fn repeat(&self, card: &PyCard, now: DateTime<Utc>) -> HashMap<Rating, PySchedulingInfo> {
+        self.inner
+            .repeat(card.inner.clone(), now)
+            .into_iter()
+            .map(|(rating, info)| (rating, info.into()))
+            .collect()
+    }
+
+    #[pyo3(signature = (card, now, rating, review_duration=None))]
+    fn next(
+        &self,
+        card: &PyCard,
+        now: DateTime<Utc>,
+        rating: Rating,
+        review_duration: Option<u32>,
+    ) -> PySchedulingInfo {
+        let card = card.inner.clone();
+        match review_duration {
+            Some(duration) => self.inner.next_with_duration(card, now, rating, duration),
+            None => self.inner.next(card, now, rating),
+        }
+        .into()
+    }
+
+    fn __getnewargs__(&self) -> (PyParameters,) {
+        (self.parameters.clone(),)
+    }
+}
+
+#[pymodule]
+fn rs_fsrs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Rating>()?;
+    m.add_class::<State>()?;
+    m.add_class::<ReviewTiming>()?;
+    m.add_class::<PyParameters>()?;
+    m.add_class::<PyCard>()?;
+    m.add_class::<PyReviewLog>()?;
+    m.add_class::<PySchedulingInfo>()?;
+    m.add_class::<PyFSRS>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    fn run(code: &std::ffi::CStr) {
+        Python::initialize();
+        Python::attach(|py| {
+            let globals = PyDict::new(py);
+            let module = pyo3::wrap_pymodule!(rs_fsrs)(py);
+            py.import("sys")
+                .and_then(|sys| sys.getattr("modules"))
+                .and_then(|modules| modules.set_item("rs_fsrs", module))
+                .unwrap();
+            py.run(code, Some(&globals), None).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_repeat_and_next() {
+        run(c"
+from datetime import datetime, timezone
+from rs_fsrs import FSRS, Card, Parameters, Rating, ReviewTiming, State
+
+now = datetime(2022, 11, 29, 12, 30, tzinfo=timezone.utc)
+fsrs = FSRS(Parameters(enable_short_term=False))
+card = Card(now)
+record_log = fsrs.repeat(card, now)
+assert set(record_log) == {Rating.Again, Rating.Hard, Rating.Good, Rating.Easy}
+
+info = fsrs.next(card, now, Rating.Good)
+assert info.card == record_log[Rating.Good].card
+assert info.card.state == State.Review
+assert info.review_log.rating == Rating.Good
+assert info.review_log.review_duration is None
+assert info.review_log.timing == ReviewTiming.OnTime
+assert (info.card.due - now).days == info.card.scheduled_days
+assert 0.0 < info.card.get_retrievability(info.card.due) < 1.0
+");
+    }
+
+    #[test]
+    fn test_pickle() {
+        run(c"
+import pickle
+from datetime import datetime, timezone
+from rs_fsrs import FSRS, Card, Parameters, Rating
+
+now = datetime(2022, 11, 29, 12, 30, tzinfo=timezone.utc)
+fsrs = pickle.loads(pickle.dumps(FSRS(Parameters(request_retention=0.8))))
+assert fsrs.parameters.request_retention == 0.8
+
+card = Card(now)
+assert card.id is None and card.preset_id is None
+card.id = 42
+card.preset_id = 7
+assert pickle.loads(pickle.dumps(card)).preset_id == 7
+card.buried_until = now
+assert pickle.loads(pickle.dumps(card)).buried_until == now
+assert card.is_due(now) and not card.suspended
+card.buried_until = None
+
+info = fsrs.next(card, now, Rating.Easy, review_duration=3000)
+restored = pickle.loads(pickle.dumps(info))
+assert restored.card == info.card
+assert restored.review_log == info.review_log
+assert restored.review_log.review_duration == 3000
+assert (restored.review_log.card_id, restored.review_log.preset_id) == (42, 7)
+assert pickle.loads(pickle.dumps(Rating.Hard)) == Rating.Hard
+");
+    }
+}
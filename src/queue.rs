@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+
+use crate::Card;
+
+/// Cards due at `now`, skipping suspended cards and cards still buried.
+pub fn due_cards<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    now: DateTime<Utc>,
+) -> impl Iterator<Item = &'a Card> {
+    cards.into_iter().filter(move |card| card.is_due(now))
+}
@@ -0,0 +1,203 @@
+use core::array;
+use core::iter::Zip;
+use core::ops::{Index, IndexMut};
+use core::slice;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Rating, SchedulingInfo};
+
+const RATINGS: [Rating; 4] = [Rating::Again, Rating::Hard, Rating::Good, Rating::Easy];
+
+/// Scheduling outcome of every rating, stored and iterated in rating order.
+#[derive(Debug, Clone)]
+pub struct RecordLog([SchedulingInfo; 4]);
+
+impl RecordLog {
+    /// Outcome of `rating`. Always `Some`; kept from when `RecordLog` was a `HashMap`.
+    pub const fn get(&self, rating: &Rating) -> Option<&SchedulingInfo> {
+        Some(&self.0[*rating as usize - 1])
+    }
+
+    pub const fn get_mut(&mut self, rating: &Rating) -> Option<&mut SchedulingInfo> {
+        Some(&mut self.0[*rating as usize - 1])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Rating, &SchedulingInfo)> {
+        self.into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Rating, &mut SchedulingInfo)> {
+        self.into_iter()
+    }
+}
+
+/// Outcomes for `[Again, Hard, Good, Easy]`, in that order.
+impl From<[SchedulingInfo; 4]> for RecordLog {
+    fn from(infos: [SchedulingInfo; 4]) -> Self {
+        Self(infos)
+    }
+}
+
+impl Index<Rating> for RecordLog {
+    type Output = SchedulingInfo;
+
+    fn index(&self, rating: Rating) -> &SchedulingInfo {
+        &self.0[rating as usize - 1]
+    }
+}
+
+impl Index<&Rating> for RecordLog {
+    type Output = SchedulingInfo;
+
+    fn index(&self, rating: &Rating) -> &SchedulingInfo {
+        &self[*rating]
+    }
+}
+
+impl IndexMut<Rating> for RecordLog {
+    fn index_mut(&mut self, rating: Rating) -> &mut SchedulingInfo {
+        &mut self.0[rating as usize - 1]
+    }
+}
+
+impl IndexMut<&Rating> for RecordLog {
+    fn index_mut(&mut self, rating: &Rating) -> &mut SchedulingInfo {
+        &mut self[*rating]
+    }
+}
+
+/// Iterator over the outcomes of a [`RecordLog`], in rating order.
+#[derive(Debug)]
+pub struct IntoIter(Zip<array::IntoIter<Rating, 4>, array::IntoIter<SchedulingInfo, 4>>);
+
+impl Iterator for IntoIter {
+    type Item = (Rating, SchedulingInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterator over references to the outcomes of a [`RecordLog`], in rating order.
+#[derive(Debug)]
+pub struct Iter<'a>(Zip<array::IntoIter<Rating, 4>, slice::Iter<'a, SchedulingInfo>>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Rating, &'a SchedulingInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Iterator over mutable references to the outcomes of a [`RecordLog`], in rating order.
+#[derive(Debug)]
+pub struct IterMut<'a>(Zip<array::IntoIter<Rating, 4>, slice::IterMut<'a, SchedulingInfo>>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (Rating, &'a mut SchedulingInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl IntoIterator for RecordLog {
+    type Item = (Rating, SchedulingInfo);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter(RATINGS.into_iter().zip(self.0))
+    }
+}
+
+impl<'a> IntoIterator for &'a RecordLog {
+    type Item = (Rating, &'a SchedulingInfo);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        Iter(RATINGS.into_iter().zip(self.0.iter()))
+    }
+}
+
+impl<'a> IntoIterator for &'a mut RecordLog {
+    type Item = (Rating, &'a mut SchedulingInfo);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        IterMut(RATINGS.into_iter().zip(self.0.iter_mut()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<RecordLog> for HashMap<Rating, SchedulingInfo> {
+    fn from(record_log: RecordLog) -> Self {
+        record_log.into_iter().collect()
+    }
+}
+
+/// Fails with the first rating missing from the map.
+#[cfg(feature = "std")]
+impl TryFrom<HashMap<Rating, SchedulingInfo>> for RecordLog {
+    type Error = Rating;
+
+    fn try_from(mut map: HashMap<Rating, SchedulingInfo>) -> Result<Self, Rating> {
+        let [again, hard, good, easy] = RATINGS.map(|rating| map.remove(&rating).ok_or(rating));
+        Ok(Self([again?, hard?, good?, easy?]))
+    }
+}
+
+/// Serialized as a map from rating to scheduling info.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RecordLog {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RecordLog {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = RecordLog;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("a map with an entry for every rating")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> Result<RecordLog, A::Error> {
+                let mut infos: [Option<SchedulingInfo>; 4] = Default::default();
+                while let Some((rating, info)) = map.next_entry::<Rating, SchedulingInfo>()? {
+                    infos[rating as usize - 1] = Some(info);
+                }
+                if let Some(index) = infos.iter().position(Option::is_none) {
+                    let rating = RATINGS[index];
+                    return Err(serde::de::Error::custom(format_args!(
+                        "missing {rating:?} in record log"
+                    )));
+                }
+                Ok(RecordLog(infos.map(Option::unwrap)))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
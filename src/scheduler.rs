@@ -1,23 +1,25 @@
 use chrono::{DateTime, Utc};
 
-use crate::Seed;
 use crate::models::State::*;
 use crate::{
-    Card, Parameters, Rating, ReviewLog,
-    models::{RecordLog, SchedulingInfo},
+    Card, IntervalInfo, Parameters, Rating, RecordLog, ReviewLog, ReviewTiming, SchedulingInfo,
 };
+use crate::{LeechAction, QueueStatus, ReviewSeed, SeedPolicy};
 
 #[derive(Debug, Clone)]
-pub struct Scheduler {
-    pub parameters: Parameters,
+pub struct Scheduler<'a> {
+    pub parameters: &'a Parameters,
+    pub seed: ReviewSeed<'a>,
+    /// Retention intervals are computed for, `Parameters::request_retention` unless overridden
+    pub request_retention: f64,
     pub last: Card,
     pub current: Card,
     pub now: DateTime<Utc>,
-    pub next: RecordLog,
+    pub next: [Option<SchedulingInfo>; 4],
 }
 
-impl Scheduler {
-    pub fn new(parameters: Parameters, card: Card, now: DateTime<Utc>) -> Self {
+impl<'a> Scheduler<'a> {
+    pub fn new(parameters: &'a Parameters, card: Card, now: DateTime<Utc>) -> Self {
         let mut current_card: Card = card.clone();
         current_card.elapsed_days = match card.state {
             New => 0,
@@ -25,41 +27,135 @@ impl Scheduler {
         };
         current_card.last_review = now;
         current_card.reps += 1;
-        let mut scheduler = Self {
+        let seed = Self::review_seed(parameters, &current_card, now);
+        Self {
             parameters,
+            seed,
+            request_retention: parameters.request_retention,
             last: card,
             current: current_card,
             now,
-            next: RecordLog::new(),
-        };
-        scheduler.init_seed();
+            next: Default::default(),
+        }
+    }
+
+    /// Retrievability of the card at this review, counting at most
+    /// `Parameters::overdue_cap` scheduled intervals of elapsed time.
+    pub fn retrievability(&self) -> f64 {
+        if self.last.state == New {
+            return 0.0;
+        }
+        let mut elapsed_days = self.current.elapsed_days as f64;
+        if let Some(cap) = self.parameters.overdue_cap {
+            elapsed_days = elapsed_days.min(cap * self.last.scheduled_days.max(1) as f64);
+        }
+        self.parameters
+            .retrievability(elapsed_days, self.last.stability)
+    }
+
+    /// `Parameters::early_review_damping`, if it applies to this review.
+    pub const fn same_day_damping(&self) -> Option<f64> {
+        match self.parameters.early_review_damping {
+            Some(damping) if !matches!(self.last.state, New) && self.current.elapsed_days == 0 => {
+                Some(damping)
+            }
+            _ => None,
+        }
+    }
 
-        scheduler
+    /// Stability after reviewing the card with `rating` outside of learning steps.
+    pub fn review_stability(&self, retrievability: f64, rating: Rating) -> f64 {
+        let (difficulty, stability) = (self.last.difficulty, self.last.stability);
+        if let Some(damping) = self.same_day_damping() {
+            return self
+                .parameters
+                .same_day_stability(stability, rating, damping);
+        }
+        match rating {
+            Rating::Again => {
+                self.parameters
+                    .next_forget_stability(difficulty, stability, retrievability)
+            }
+            _ => {
+                self.parameters
+                    .next_recall_stability(difficulty, stability, retrievability, rating)
+            }
+        }
     }
 
     pub const fn build_log(&self, rating: Rating) -> ReviewLog {
         ReviewLog {
+            card_id: self.current.id,
+            preset_id: self.current.preset_id,
             rating,
             state: self.current.state,
             elapsed_days: self.current.elapsed_days,
             scheduled_days: self.current.scheduled_days,
             reviewed_date: self.now,
+            review_duration: None,
+            timing: ReviewTiming::new(self.current.elapsed_days, self.current.scheduled_days),
         }
     }
 
-    fn init_seed(&mut self) {
-        let time = self.now.timestamp_millis();
-        let reps = self.current.reps;
-        let mul = self.current.difficulty * self.current.stability;
-        self.parameters.seed = Seed::new(format!("{time}_{reps}_{mul}"));
+    pub const fn cached(&self, rating: Rating) -> Option<&SchedulingInfo> {
+        self.next[rating as usize - 1].as_ref()
+    }
+
+    pub const fn cache(&mut self, rating: Rating, info: SchedulingInfo) {
+        self.next[rating as usize - 1] = Some(info);
+    }
+
+    /// Mark `info` as a leech and apply `Parameters::leech_action` if it lapsed
+    /// the card into a leech.
+    pub fn apply_leech_policy(&self, mut info: SchedulingInfo) -> SchedulingInfo {
+        if info.card.lapses <= self.last.lapses || !self.parameters.is_leech(info.card.lapses) {
+            return info;
+        }
+        let action = self.parameters.leech_action;
+        match action {
+            LeechAction::Tag => {}
+            LeechAction::Suspend => info.card.status = QueueStatus::Suspended,
+            LeechAction::Reset => {
+                info.card = Card {
+                    id: info.card.id,
+                    preset_id: info.card.preset_id,
+                    ..Card::new_at(self.now)
+                };
+                info.interval = None;
+            }
+        }
+        info.leech = Some(action);
+        info
+    }
+
+    /// Interval for `stability`, fuzzed with the seed of this review.
+    pub fn next_interval_info(&self, stability: f64, elapsed_days: i64) -> IntervalInfo {
+        self.parameters.next_interval_info_with_retention(
+            stability,
+            elapsed_days,
+            &self.seed,
+            self.request_retention,
+        )
+    }
+
+    fn review_seed(parameters: &'a Parameters, card: &Card, now: DateTime<Utc>) -> ReviewSeed<'a> {
+        let reps = card.reps;
+        match (parameters.seed_policy, card.id) {
+            (SeedPolicy::Fixed, _) => ReviewSeed::Fixed(&parameters.seed),
+            (SeedPolicy::CardIdAndReps, Some(id)) => ReviewSeed::CardIdAndReps { id, reps },
+            (SeedPolicy::Card | SeedPolicy::CardIdAndReps, _) => ReviewSeed::Card {
+                time: now.timestamp_millis(),
+                reps,
+                mul: card.difficulty * card.stability,
+            },
+        }
     }
 }
 
 pub trait ImplScheduler {
     fn preview(&mut self) -> RecordLog {
-        Rating::iter()
-            .map(|&rating| (rating, self.review(rating)))
-            .collect()
+        use Rating::*;
+        RecordLog::from([Again, Hard, Good, Easy].map(|rating| self.review(rating)))
     }
     fn review(&mut self, rating: Rating) -> SchedulingInfo;
 }
@@ -1,25 +1,34 @@
 use chrono::{DateTime, Duration, Utc};
 
-use crate::{Card, ImplScheduler, Parameters, Rating, SchedulingInfo, scheduler::Scheduler};
+use crate::{
+    Card, ImplScheduler, IntervalInfo, Parameters, Rating, SchedulingInfo, scheduler::Scheduler,
+};
 use crate::{Rating::*, State::*};
-pub struct BasicScheduler {
-    pub scheduler: Scheduler,
+pub struct BasicScheduler<'a> {
+    pub scheduler: Scheduler<'a>,
 }
 
-impl BasicScheduler {
-    pub fn new(parameters: Parameters, card: Card, now: DateTime<Utc>) -> Self {
+impl<'a> BasicScheduler<'a> {
+    pub fn new(parameters: &'a Parameters, card: Card, now: DateTime<Utc>) -> Self {
         Self {
             scheduler: Scheduler::new(parameters, card, now),
         }
     }
+
+    /// Compute intervals for `request_retention` instead of the one in the parameters.
+    pub const fn with_retention(mut self, request_retention: f64) -> Self {
+        self.scheduler.request_retention = request_retention;
+        self
+    }
     fn new_state(&mut self, rating: Rating) -> SchedulingInfo {
-        if let Some(exist) = self.scheduler.next.get(&rating) {
+        if let Some(exist) = self.scheduler.cached(rating) {
             return exist.clone();
         }
 
         let mut next = self.scheduler.current.clone();
         next.difficulty = self.scheduler.parameters.init_difficulty(rating);
         next.stability = self.scheduler.parameters.init_stability(rating);
+        let mut interval = None;
 
         match rating {
             Again => {
@@ -40,29 +49,32 @@ impl BasicScheduler {
             Easy => {
                 let easy_interval = self
                     .scheduler
-                    .parameters
-                    .next_interval(next.stability, next.elapsed_days);
-                next.scheduled_days = easy_interval as i64;
-                next.due = self.scheduler.now + Duration::days(easy_interval as i64);
+                    .next_interval_info(next.stability, next.elapsed_days);
+                next.scheduled_days = easy_interval.interval as i64;
+                next.due = self.scheduler.now + Duration::days(easy_interval.interval as i64);
                 next.state = Review;
+                interval = Some(easy_interval);
             }
         };
         let item = SchedulingInfo {
             card: next,
             review_log: self.scheduler.build_log(rating),
+            interval,
+            leech: None,
         };
 
-        self.scheduler.next.insert(rating, item.clone());
+        self.scheduler.cache(rating, item.clone());
         item
     }
 
     fn learning_state(&mut self, rating: Rating) -> SchedulingInfo {
-        if let Some(exist) = self.scheduler.next.get(&rating) {
+        if let Some(exist) = self.scheduler.cached(rating) {
             return exist.clone();
         }
 
         let mut next = self.scheduler.current.clone();
-        let interval = self.scheduler.current.elapsed_days;
+        let elapsed_days = self.scheduler.current.elapsed_days;
+        let mut interval = None;
         next.difficulty = self
             .scheduler
             .parameters
@@ -86,11 +98,11 @@ impl BasicScheduler {
             Good => {
                 let good_interval = self
                     .scheduler
-                    .parameters
-                    .next_interval(next.stability, interval);
-                next.scheduled_days = good_interval as i64;
-                next.due = self.scheduler.now + Duration::days(good_interval as i64);
+                    .next_interval_info(next.stability, elapsed_days);
+                next.scheduled_days = good_interval.interval as i64;
+                next.due = self.scheduler.now + Duration::days(good_interval.interval as i64);
                 next.state = Review;
+                interval = Some(good_interval);
             }
             Easy => {
                 let good_stability = self
@@ -99,37 +111,41 @@ impl BasicScheduler {
                     .short_term_stability(self.scheduler.last.stability, Good);
                 let good_interval = self
                     .scheduler
-                    .parameters
-                    .next_interval(good_stability, interval);
-                let easy_interval = self
+                    .next_interval_info(good_stability, elapsed_days)
+                    .interval;
+                let easy_info = self
                     .scheduler
-                    .parameters
-                    .next_interval(next.stability, interval)
-                    .max(good_interval + 1.0);
+                    .next_interval_info(next.stability, elapsed_days);
+                let easy_interval = easy_info.interval.max(good_interval + 1.0);
                 next.scheduled_days = easy_interval as i64;
                 next.due = self.scheduler.now + Duration::days(easy_interval as i64);
                 next.state = Review;
+                interval = Some(IntervalInfo {
+                    interval: easy_interval,
+                    ..easy_info
+                });
             }
         }
         let item = SchedulingInfo {
             card: next,
             review_log: self.scheduler.build_log(rating),
+            interval,
+            leech: None,
         };
 
-        self.scheduler.next.insert(rating, item.clone());
+        self.scheduler.cache(rating, item.clone());
         item
     }
 
     fn review_state(&mut self, rating: Rating) -> SchedulingInfo {
-        if let Some(exist) = self.scheduler.next.get(&rating) {
+        if let Some(exist) = self.scheduler.cached(rating) {
             return exist.clone();
         }
 
         let next = self.scheduler.current.clone();
         let interval = self.scheduler.current.elapsed_days;
-        let stability = self.scheduler.last.stability;
         let difficulty = self.scheduler.last.difficulty;
-        let retrievability = self.scheduler.last.get_retrievability(self.scheduler.now);
+        let retrievability = self.scheduler.retrievability();
 
         let mut next_again = next.clone();
         let mut next_hard = next.clone();
@@ -142,10 +158,9 @@ impl BasicScheduler {
             &mut next_good,
             &mut next_easy,
             difficulty,
-            stability,
             retrievability,
         );
-        self.next_interval(
+        let [hard_interval, good_interval, easy_interval] = self.next_interval(
             &mut next_again,
             &mut next_hard,
             &mut next_good,
@@ -163,29 +178,36 @@ impl BasicScheduler {
         let item_again = SchedulingInfo {
             card: next_again,
             review_log: self.scheduler.build_log(Again),
+            interval: None,
+            leech: None,
         };
         let item_hard = SchedulingInfo {
             card: next_hard,
             review_log: self.scheduler.build_log(Hard),
+            interval: Some(hard_interval),
+            leech: None,
         };
         let item_good = SchedulingInfo {
             card: next_good,
             review_log: self.scheduler.build_log(Good),
+            interval: Some(good_interval),
+            leech: None,
         };
         let item_easy = SchedulingInfo {
             card: next_easy,
             review_log: self.scheduler.build_log(Easy),
+            interval: Some(easy_interval),
+            leech: None,
         };
 
-        self.scheduler.next.insert(Again, item_again);
-        self.scheduler.next.insert(Hard, item_hard);
-        self.scheduler.next.insert(Good, item_good);
-        self.scheduler.next.insert(Easy, item_easy);
+        self.scheduler.cache(Again, item_again);
+        self.scheduler.cache(Hard, item_hard);
+        self.scheduler.cache(Good, item_good);
+        self.scheduler.cache(Easy, item_easy);
 
-        self.scheduler.next.get(&rating).unwrap().to_owned()
+        self.scheduler.cached(rating).unwrap().clone()
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn next_difficulty_stability(
         &self,
         next_again: &mut Card,
@@ -193,38 +215,19 @@ impl BasicScheduler {
         next_good: &mut Card,
         next_easy: &mut Card,
         difficulty: f64,
-        stability: f64,
         retrievability: f64,
     ) {
         next_again.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Again);
-        next_again.stability =
-            self.scheduler
-                .parameters
-                .next_forget_stability(difficulty, stability, retrievability);
+        next_again.stability = self.scheduler.review_stability(retrievability, Again);
 
         next_hard.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Hard);
-        next_hard.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Hard,
-        );
+        next_hard.stability = self.scheduler.review_stability(retrievability, Hard);
 
         next_good.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Good);
-        next_good.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Good,
-        );
+        next_good.stability = self.scheduler.review_stability(retrievability, Good);
 
         next_easy.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Easy);
-        next_easy.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Easy,
-        );
+        next_easy.stability = self.scheduler.review_stability(retrievability, Easy);
     }
 
     fn next_interval(
@@ -234,22 +237,19 @@ impl BasicScheduler {
         next_good: &mut Card,
         next_easy: &mut Card,
         elapsed_days: i64,
-    ) {
-        let mut hard_interval = self
+    ) -> [IntervalInfo; 3] {
+        let hard_info = self
             .scheduler
-            .parameters
-            .next_interval(next_hard.stability, elapsed_days);
-        let mut good_interval = self
+            .next_interval_info(next_hard.stability, elapsed_days);
+        let good_info = self
             .scheduler
-            .parameters
-            .next_interval(next_good.stability, elapsed_days);
-        hard_interval = hard_interval.min(good_interval);
-        good_interval = good_interval.max(hard_interval + 1.0);
-        let easy_interval = self
+            .next_interval_info(next_good.stability, elapsed_days);
+        let easy_info = self
             .scheduler
-            .parameters
-            .next_interval(next_easy.stability, elapsed_days)
-            .max(good_interval + 1.0);
+            .next_interval_info(next_easy.stability, elapsed_days);
+        let hard_interval = hard_info.interval.min(good_info.interval);
+        let good_interval = good_info.interval.max(hard_interval + 1.0);
+        let easy_interval = easy_info.interval.max(good_interval + 1.0);
 
         next_again.scheduled_days = 0;
         next_again.due = self.scheduler.now + Duration::minutes(5);
@@ -262,6 +262,21 @@ impl BasicScheduler {
 
         next_easy.scheduled_days = easy_interval as i64;
         next_easy.due = self.scheduler.now + Duration::days(easy_interval as i64);
+
+        [
+            IntervalInfo {
+                interval: hard_interval,
+                ..hard_info
+            },
+            IntervalInfo {
+                interval: good_interval,
+                ..good_info
+            },
+            IntervalInfo {
+                interval: easy_interval,
+                ..easy_info
+            },
+        ]
     }
 
     fn next_state(
@@ -278,12 +293,13 @@ impl BasicScheduler {
     }
 }
 
-impl ImplScheduler for BasicScheduler {
+impl ImplScheduler for BasicScheduler<'_> {
     fn review(&mut self, rating: Rating) -> SchedulingInfo {
-        match self.scheduler.last.state {
+        let info = match self.scheduler.last.state {
             New => self.new_state(rating),
             Learning | Relearning => self.learning_state(rating),
             Review => self.review_state(rating),
-        }
+        };
+        self.scheduler.apply_leech_policy(info)
     }
 }
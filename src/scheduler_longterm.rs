@@ -1,21 +1,27 @@
 use chrono::{DateTime, Duration, Utc};
 
-use crate::{Card, ImplScheduler, Parameters, Rating, Scheduler, SchedulingInfo};
+use crate::{Card, ImplScheduler, IntervalInfo, Parameters, Rating, Scheduler, SchedulingInfo};
 use crate::{Rating::*, State::*};
 
-pub struct LongtermScheduler {
-    scheduler: Scheduler,
+pub struct LongtermScheduler<'a> {
+    scheduler: Scheduler<'a>,
 }
 
-impl LongtermScheduler {
-    pub fn new(parameters: Parameters, card: Card, now: DateTime<Utc>) -> Self {
+impl<'a> LongtermScheduler<'a> {
+    pub fn new(parameters: &'a Parameters, card: Card, now: DateTime<Utc>) -> Self {
         Self {
             scheduler: Scheduler::new(parameters, card, now),
         }
     }
 
+    /// Compute intervals for `request_retention` instead of the one in the parameters.
+    pub const fn with_retention(mut self, request_retention: f64) -> Self {
+        self.scheduler.request_retention = request_retention;
+        self
+    }
+
     fn new_state(&mut self, rating: Rating) -> SchedulingInfo {
-        if let Some(exist) = self.scheduler.next.get(&rating) {
+        if let Some(exist) = self.scheduler.cached(rating) {
             return exist.clone();
         }
 
@@ -34,7 +40,7 @@ impl LongtermScheduler {
             &mut next_good,
             &mut next_easy,
         );
-        self.next_interval(
+        let intervals = self.next_interval(
             &mut next_again,
             &mut next_hard,
             &mut next_good,
@@ -47,9 +53,9 @@ impl LongtermScheduler {
             &mut next_good,
             &mut next_easy,
         );
-        self.update_next(&next_again, &next_hard, &next_good, &next_easy);
+        self.update_next(&next_again, &next_hard, &next_good, &next_easy, intervals);
 
-        self.scheduler.next.get(&rating).unwrap().to_owned()
+        self.scheduler.cached(rating).unwrap().clone()
     }
 
     fn learning_state(&mut self, rating: Rating) -> SchedulingInfo {
@@ -57,15 +63,14 @@ impl LongtermScheduler {
     }
 
     fn review_state(&mut self, rating: Rating) -> SchedulingInfo {
-        if let Some(exist) = self.scheduler.next.get(&rating) {
+        if let Some(exist) = self.scheduler.cached(rating) {
             return exist.clone();
         }
 
         let next = self.scheduler.current.clone();
         let interval = self.scheduler.current.elapsed_days;
-        let stability = self.scheduler.last.stability;
         let difficulty = self.scheduler.last.difficulty;
-        let retrievability = self.scheduler.last.get_retrievability(self.scheduler.now);
+        let retrievability = self.scheduler.retrievability();
 
         let mut next_again = next.clone();
         let mut next_hard = next.clone();
@@ -78,10 +83,9 @@ impl LongtermScheduler {
             &mut next_good,
             &mut next_easy,
             difficulty,
-            stability,
             retrievability,
         );
-        self.next_interval(
+        let intervals = self.next_interval(
             &mut next_again,
             &mut next_hard,
             &mut next_good,
@@ -96,8 +100,8 @@ impl LongtermScheduler {
         );
         next_again.lapses += 1;
 
-        self.update_next(&next_again, &next_hard, &next_good, &next_easy);
-        self.scheduler.next.get(&rating).unwrap().to_owned()
+        self.update_next(&next_again, &next_hard, &next_good, &next_easy, intervals);
+        self.scheduler.cached(rating).unwrap().clone()
     }
 
     fn init_difficulty_stability(
@@ -120,7 +124,6 @@ impl LongtermScheduler {
         next_easy.stability = self.scheduler.parameters.init_stability(Easy);
     }
 
-    #[allow(clippy::too_many_arguments)]
     fn next_difficulty_stability(
         &self,
         next_again: &mut Card,
@@ -128,38 +131,19 @@ impl LongtermScheduler {
         next_good: &mut Card,
         next_easy: &mut Card,
         difficulty: f64,
-        stability: f64,
         retrievability: f64,
     ) {
         next_again.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Again);
-        next_again.stability =
-            self.scheduler
-                .parameters
-                .next_forget_stability(difficulty, stability, retrievability);
+        next_again.stability = self.scheduler.review_stability(retrievability, Again);
 
         next_hard.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Hard);
-        next_hard.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Hard,
-        );
+        next_hard.stability = self.scheduler.review_stability(retrievability, Hard);
 
         next_good.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Good);
-        next_good.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Good,
-        );
+        next_good.stability = self.scheduler.review_stability(retrievability, Good);
 
         next_easy.difficulty = self.scheduler.parameters.next_difficulty(difficulty, Easy);
-        next_easy.stability = self.scheduler.parameters.next_recall_stability(
-            difficulty,
-            stability,
-            retrievability,
-            Easy,
-        );
+        next_easy.stability = self.scheduler.review_stability(retrievability, Easy);
     }
 
     fn next_interval(
@@ -169,28 +153,24 @@ impl LongtermScheduler {
         next_good: &mut Card,
         next_easy: &mut Card,
         elapsed_days: i64,
-    ) {
-        let mut again_interval = self
+    ) -> [IntervalInfo; 4] {
+        let again_info = self
             .scheduler
-            .parameters
-            .next_interval(next_again.stability, elapsed_days);
-        let mut hard_interval = self
+            .next_interval_info(next_again.stability, elapsed_days);
+        let hard_info = self
             .scheduler
-            .parameters
-            .next_interval(next_hard.stability, elapsed_days);
-        let mut good_interval = self
+            .next_interval_info(next_hard.stability, elapsed_days);
+        let good_info = self
             .scheduler
-            .parameters
-            .next_interval(next_good.stability, elapsed_days);
-        let mut easy_interval = self
+            .next_interval_info(next_good.stability, elapsed_days);
+        let easy_info = self
             .scheduler
-            .parameters
-            .next_interval(next_easy.stability, elapsed_days);
+            .next_interval_info(next_easy.stability, elapsed_days);
 
-        again_interval = again_interval.min(hard_interval);
-        hard_interval = hard_interval.max(again_interval + 1.0);
-        good_interval = good_interval.max(hard_interval + 1.0);
-        easy_interval = easy_interval.max(good_interval + 1.0);
+        let again_interval = again_info.interval.min(hard_info.interval);
+        let hard_interval = hard_info.interval.max(again_interval + 1.0);
+        let good_interval = good_info.interval.max(hard_interval + 1.0);
+        let easy_interval = easy_info.interval.max(good_interval + 1.0);
 
         next_again.scheduled_days = again_interval as i64;
         next_again.due = self.scheduler.now + Duration::days(again_interval as i64);
@@ -203,6 +183,25 @@ impl LongtermScheduler {
 
         next_easy.scheduled_days = easy_interval as i64;
         next_easy.due = self.scheduler.now + Duration::days(easy_interval as i64);
+
+        [
+            IntervalInfo {
+                interval: again_interval,
+                ..again_info
+            },
+            IntervalInfo {
+                interval: hard_interval,
+                ..hard_info
+            },
+            IntervalInfo {
+                interval: good_interval,
+                ..good_info
+            },
+            IntervalInfo {
+                interval: easy_interval,
+                ..easy_info
+            },
+        ]
     }
 
     fn next_state(
@@ -224,37 +223,48 @@ impl LongtermScheduler {
         next_hard: &Card,
         next_good: &Card,
         next_easy: &Card,
+        intervals: [IntervalInfo; 4],
     ) {
+        let [again_interval, hard_interval, good_interval, easy_interval] = intervals;
         let item_again = SchedulingInfo {
             card: next_again.clone(),
             review_log: self.scheduler.build_log(Again),
+            interval: Some(again_interval),
+            leech: None,
         };
         let item_hard = SchedulingInfo {
             card: next_hard.clone(),
             review_log: self.scheduler.build_log(Hard),
+            interval: Some(hard_interval),
+            leech: None,
         };
         let item_good = SchedulingInfo {
             card: next_good.clone(),
             review_log: self.scheduler.build_log(Good),
+            interval: Some(good_interval),
+            leech: None,
         };
         let item_easy = SchedulingInfo {
             card: next_easy.clone(),
             review_log: self.scheduler.build_log(Easy),
+            interval: Some(easy_interval),
+            leech: None,
         };
 
-        self.scheduler.next.insert(Again, item_again);
-        self.scheduler.next.insert(Hard, item_hard);
-        self.scheduler.next.insert(Good, item_good);
-        self.scheduler.next.insert(Easy, item_easy);
+        self.scheduler.cache(Again, item_again);
+        self.scheduler.cache(Hard, item_hard);
+        self.scheduler.cache(Good, item_good);
+        self.scheduler.cache(Easy, item_easy);
     }
 }
 
-impl ImplScheduler for LongtermScheduler {
+impl ImplScheduler for LongtermScheduler<'_> {
     fn review(&mut self, rating: Rating) -> SchedulingInfo {
-        match self.scheduler.last.state {
+        let info = match self.scheduler.last.state {
             New => self.new_state(rating),
             Learning | Relearning => self.learning_state(rating),
             Review => self.review_state(rating),
-        }
+        };
+        self.scheduler.apply_leech_policy(info)
     }
 }
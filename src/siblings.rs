@@ -0,0 +1,99 @@
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{Card, Parameters, QueueStatus, Rating, RecordLog, State};
+
+/// Group cards by the note (or any other sibling key) they were generated from.
+#[cfg(feature = "std")]
+pub fn group_siblings<K, I>(cards: I) -> HashMap<K, Vec<Card>>
+where
+    K: Eq + Hash,
+    I: IntoIterator<Item = (K, Card)>,
+{
+    let mut groups: HashMap<K, Vec<Card>> = HashMap::new();
+    for (key, card) in cards {
+        groups.entry(key).or_default().push(card);
+    }
+    groups
+}
+
+/// Bury every sibling that is due today (UTC) until the start of the next day.
+/// Suspended siblings and siblings buried for longer are left alone.
+pub fn bury_siblings(siblings: &mut [Card], now: DateTime<Utc>) {
+    let today = now.date_naive();
+    let tomorrow = (today + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc();
+    for sibling in siblings.iter_mut() {
+        let buried_longer = match sibling.status {
+            QueueStatus::Active => false,
+            QueueStatus::Suspended => true,
+            QueueStatus::Buried(until) => until >= tomorrow,
+        };
+        if sibling.due.date_naive() <= today && !buried_longer {
+            sibling.status = QueueStatus::Buried(tomorrow);
+        }
+    }
+}
+
+/// Move the review intervals of `record_log` inside their fuzz ranges so that they
+/// keep at least `sibling_spacing` days away from the due dates of their siblings.
+/// A moved interval stays strictly between the intervals of the ratings next to it,
+/// and is left untouched when no day in its range is free.
+pub fn space_from_siblings(
+    parameters: &Parameters,
+    record_log: &mut RecordLog,
+    now: DateTime<Utc>,
+    siblings: &[Card],
+) {
+    let taken: Vec<NaiveDate> = siblings
+        .iter()
+        .filter(|sibling| sibling.state != State::New)
+        .map(|sibling| sibling.due.date_naive())
+        .collect();
+    let spacing = parameters.sibling_spacing;
+    let is_free = |days: i64| {
+        let date = (now + Duration::days(days)).date_naive();
+        taken
+            .iter()
+            .all(|&other| (date - other).num_days().abs() >= spacing)
+    };
+
+    let ratings = Rating::iter().as_slice();
+    for (index, &rating) in ratings.iter().enumerate() {
+        let lower = index
+            .checked_sub(1)
+            .map(|previous| record_log[ratings[previous]].card.scheduled_days);
+        let upper = ratings
+            .get(index + 1)
+            .map(|&next| record_log[next].card.scheduled_days);
+        let info = &mut record_log[rating];
+        let Some(interval) = info.interval.as_mut() else {
+            continue;
+        };
+        let card = &mut info.card;
+        if card.state != State::Review
+            || interval.min_interval == interval.max_interval
+            || is_free(card.scheduled_days)
+        {
+            continue;
+        }
+
+        let mut candidates: Vec<i64> = (interval.min_interval..=interval.max_interval)
+            .filter(|&days| lower.is_none_or(|lower| days > lower))
+            .filter(|&days| upper.is_none_or(|upper| days < upper))
+            .collect();
+        candidates.sort_by_key(|&days| (days - card.scheduled_days).abs());
+
+        if let Some(days) = candidates.into_iter().find(|&days| is_free(days)) {
+            card.scheduled_days = days;
+            card.due = now + Duration::days(days);
+            interval.interval = days as f64;
+        }
+    }
+}
@@ -0,0 +1,455 @@
+//! Collection statistics for dashboards, computed from cards and review logs.
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Card, QueueStatus, Rating, ReviewLog, State};
+
+/// Reviews that were recalled (rated anything but `Again`) out of all reviews.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Retention {
+    pub reviews: usize,
+    pub passed: usize,
+}
+
+impl Retention {
+    /// `passed / reviews`, `None` without reviews.
+    pub fn rate(&self) -> Option<f64> {
+        (self.reviews > 0).then(|| self.passed as f64 / self.reviews as f64)
+    }
+
+    fn add(&mut self, log: &ReviewLog) {
+        self.reviews += 1;
+        if log.rating != Rating::Again {
+            self.passed += 1;
+        }
+    }
+}
+
+/// Time spent on reviews that recorded a duration, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReviewTime {
+    pub reviews: usize,
+    pub total: u64,
+}
+
+impl ReviewTime {
+    /// `total / reviews`, `None` without reviews.
+    pub fn mean(&self) -> Option<f64> {
+        (self.reviews > 0).then(|| self.total as f64 / self.reviews as f64)
+    }
+
+    const fn add(&mut self, log: &ReviewLog) {
+        if let Some(duration) = log.review_duration {
+            self.reviews += 1;
+            self.total += duration as u64;
+        }
+    }
+}
+
+/// A value for each state cards can be reviewed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByState<T> {
+    pub new: T,
+    pub learning: T,
+    pub review: T,
+    pub relearning: T,
+}
+
+impl<T> ByState<T> {
+    pub const fn get(&self, state: State) -> &T {
+        match state {
+            State::New => &self.new,
+            State::Learning => &self.learning,
+            State::Review => &self.review,
+            State::Relearning => &self.relearning,
+        }
+    }
+
+    const fn get_mut(&mut self, state: State) -> &mut T {
+        match state {
+            State::New => &mut self.new,
+            State::Learning => &mut self.learning,
+            State::Review => &mut self.review,
+            State::Relearning => &mut self.relearning,
+        }
+    }
+}
+
+/// A value for each rating.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ByRating<T> {
+    pub again: T,
+    pub hard: T,
+    pub good: T,
+    pub easy: T,
+}
+
+impl<T> ByRating<T> {
+    pub const fn get(&self, rating: Rating) -> &T {
+        match rating {
+            Rating::Again => &self.again,
+            Rating::Hard => &self.hard,
+            Rating::Good => &self.good,
+            Rating::Easy => &self.easy,
+        }
+    }
+
+    const fn get_mut(&mut self, rating: Rating) -> &mut T {
+        match rating {
+            Rating::Again => &mut self.again,
+            Rating::Hard => &mut self.hard,
+            Rating::Good => &mut self.good,
+            Rating::Easy => &mut self.easy,
+        }
+    }
+}
+
+/// Retention split by the state cards were in when they were reviewed.
+pub type RetentionByState = ByState<Retention>;
+
+/// Most buckets a [`Distribution`] holds; the last one also counts every larger value.
+pub const MAX_BUCKETS: usize = 10_000;
+
+/// Summary and histogram of a memory-state value over reviewed cards.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub bucket_width: f64,
+    /// `buckets[i]` counts values in `[i * bucket_width, (i + 1) * bucket_width)`,
+    /// up to [`MAX_BUCKETS`] buckets
+    pub buckets: Vec<usize>,
+}
+
+impl Distribution {
+    fn new(values: impl Iterator<Item = f64>, bucket_width: f64) -> Option<Self> {
+        if !(bucket_width.is_finite() && bucket_width > 0.0) {
+            return None;
+        }
+        let mut distribution = Self {
+            count: 0,
+            mean: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            bucket_width,
+            buckets: Vec::new(),
+        };
+        let mut sum = 0.0;
+        for value in values {
+            let bucket = ((value / bucket_width) as usize).min(MAX_BUCKETS - 1);
+            if bucket >= distribution.buckets.len() {
+                distribution.buckets.resize(bucket + 1, 0);
+            }
+            distribution.buckets[bucket] += 1;
+            distribution.count += 1;
+            distribution.min = distribution.min.min(value);
+            distribution.max = distribution.max.max(value);
+            sum += value;
+        }
+        if distribution.count == 0 {
+            return None;
+        }
+        distribution.mean = sum / distribution.count as f64;
+        Some(distribution)
+    }
+}
+
+/// Expected number of cards remembered at `now`: the sum of their retrievability.
+pub fn memorized<'a>(cards: impl IntoIterator<Item = &'a Card>, now: DateTime<Utc>) -> f64 {
+    cards
+        .into_iter()
+        .map(|card| card.get_retrievability(now))
+        .sum()
+}
+
+/// Retention on reviews of cards in the `Review` state; learning and relearning
+/// steps are left out, see [`true_retention_by_state`] for those.
+pub fn true_retention<'a>(logs: impl IntoIterator<Item = &'a ReviewLog>) -> Retention {
+    let mut retention = Retention::default();
+    for log in review_state(logs) {
+        retention.add(log);
+    }
+    retention
+}
+
+pub fn true_retention_by_state<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+) -> RetentionByState {
+    let mut retention = RetentionByState::default();
+    for log in logs {
+        retention.get_mut(log.state).add(log);
+    }
+    retention
+}
+
+/// [`true_retention`] per period, keyed by the start of the period. Periods are
+/// aligned to the Unix epoch, so a period of one day starts at midnight UTC.
+///
+/// # Panics
+///
+/// If `period` is shorter than a second.
+pub fn true_retention_by_period<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+    period: Duration,
+) -> BTreeMap<DateTime<Utc>, Retention> {
+    let seconds = period.num_seconds();
+    assert!(seconds > 0, "period must be at least one second");
+
+    let mut retention: BTreeMap<DateTime<Utc>, Retention> = BTreeMap::new();
+    for log in review_state(logs) {
+        let timestamp = log.reviewed_date.timestamp();
+        let start = DateTime::from_timestamp(timestamp - timestamp.rem_euclid(seconds), 0)
+            .expect("period start is before the review");
+        retention.entry(start).or_default().add(log);
+    }
+    retention
+}
+
+pub fn review_time<'a>(logs: impl IntoIterator<Item = &'a ReviewLog>) -> ReviewTime {
+    let mut time = ReviewTime::default();
+    logs.into_iter().for_each(|log| time.add(log));
+    time
+}
+
+/// Review time per UTC day. Days without a recorded duration are left out.
+pub fn review_time_by_day<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+) -> BTreeMap<NaiveDate, ReviewTime> {
+    let mut time: BTreeMap<NaiveDate, ReviewTime> = BTreeMap::new();
+    for log in logs.into_iter().filter(|log| log.review_duration.is_some()) {
+        time.entry(log.reviewed_date.date_naive())
+            .or_default()
+            .add(log);
+    }
+    time
+}
+
+/// Review time split by the state cards were in when they were reviewed.
+pub fn review_time_by_state<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+) -> ByState<ReviewTime> {
+    let mut time = ByState::<ReviewTime>::default();
+    for log in logs {
+        time.get_mut(log.state).add(log);
+    }
+    time
+}
+
+pub fn review_time_by_rating<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+) -> ByRating<ReviewTime> {
+    let mut time = ByRating::<ReviewTime>::default();
+    for log in logs {
+        time.get_mut(log.rating).add(log);
+    }
+    time
+}
+
+/// Stability of cards that have been reviewed, `None` if there are none or
+/// `bucket_width` is not a positive number.
+pub fn stability_distribution<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    bucket_width: f64,
+) -> Option<Distribution> {
+    Distribution::new(reviewed(cards).map(|card| card.stability), bucket_width)
+}
+
+/// Difficulty of cards that have been reviewed, `None` if there are none or
+/// `bucket_width` is not a positive number.
+pub fn difficulty_distribution<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    bucket_width: f64,
+) -> Option<Distribution> {
+    Distribution::new(reviewed(cards).map(|card| card.difficulty), bucket_width)
+}
+
+/// Lapses per review over all cards, `None` before any review.
+pub fn lapse_rate<'a>(cards: impl IntoIterator<Item = &'a Card>) -> Option<f64> {
+    let (lapses, reps) = cards
+        .into_iter()
+        .fold((0i64, 0i64), |(lapses, reps), card| {
+            (lapses + card.lapses as i64, reps + card.reps as i64)
+        });
+    (reps > 0).then(|| lapses as f64 / reps as f64)
+}
+
+/// Number of reviewed cards due on each of the next `days` days (UTC); overdue
+/// cards count as due today and suspended cards are left out.
+pub fn due_histogram<'a>(
+    cards: impl IntoIterator<Item = &'a Card>,
+    now: DateTime<Utc>,
+    days: usize,
+) -> Vec<usize> {
+    let today = now.date_naive();
+    let mut histogram = vec![0; days];
+    for card in reviewed(cards).filter(|card| card.status != QueueStatus::Suspended) {
+        let day = (card.due.date_naive() - today).num_days().max(0) as usize;
+        if let Some(count) = histogram.get_mut(day) {
+            *count += 1;
+        }
+    }
+    histogram
+}
+
+fn review_state<'a>(
+    logs: impl IntoIterator<Item = &'a ReviewLog>,
+) -> impl Iterator<Item = &'a ReviewLog> {
+    logs.into_iter().filter(|log| log.state == State::Review)
+}
+
+fn reviewed<'a>(cards: impl IntoIterator<Item = &'a Card>) -> impl Iterator<Item = &'a Card> {
+    cards.into_iter().filter(|card| card.state != State::New)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FSRS, ReviewTiming};
+
+    fn now() -> DateTime<Utc> {
+        "2022-11-29T12:30:00Z".parse().unwrap()
+    }
+
+    fn log(rating: Rating, state: State, reviewed_date: DateTime<Utc>) -> ReviewLog {
+        ReviewLog {
+            card_id: None,
+            preset_id: None,
+            rating,
+            elapsed_days: 0,
+            scheduled_days: 0,
+            state,
+            reviewed_date,
+            review_duration: None,
+            timing: ReviewTiming::OnTime,
+        }
+    }
+
+    #[test]
+    fn test_true_retention() {
+        let logs = [
+            log(Rating::Good, State::Review, now()),
+            log(Rating::Again, State::Review, now() + Duration::hours(1)),
+            log(Rating::Hard, State::Learning, now() + Duration::days(1)),
+            log(Rating::Easy, State::Review, now() + Duration::days(1)),
+        ];
+
+        let retention = true_retention(&logs);
+        assert_eq!(
+            retention,
+            Retention {
+                reviews: 3,
+                passed: 2
+            }
+        );
+        assert_eq!(retention.rate(), Some(2.0 / 3.0));
+
+        let by_state = true_retention_by_state(&logs);
+        assert_eq!(by_state.get(State::Review).rate(), Some(2.0 / 3.0));
+        assert_eq!(by_state.get(State::Learning).passed, 1);
+        assert_eq!(by_state.get(State::New).rate(), None);
+
+        let by_day = true_retention_by_period(&logs, Duration::days(1));
+        let days: Vec<_> = by_day.keys().map(|day| day.to_rfc3339()).collect();
+        assert_eq!(
+            days,
+            ["2022-11-29T00:00:00+00:00", "2022-11-30T00:00:00+00:00"]
+        );
+        assert_eq!(by_day.values().next().unwrap().rate(), Some(0.5));
+        assert_eq!(by_day.values().nth(1).unwrap().reviews, 1);
+    }
+
+    #[test]
+    fn test_review_time() {
+        let timed = |rating, state, reviewed_date, duration| ReviewLog {
+            review_duration: Some(duration),
+            ..log(rating, state, reviewed_date)
+        };
+        let logs = [
+            timed(Rating::Good, State::Review, now(), 4000),
+            timed(Rating::Again, State::Review, now(), 12000),
+            timed(
+                Rating::Good,
+                State::Learning,
+                now() + Duration::days(1),
+                5000,
+            ),
+            log(Rating::Easy, State::Review, now() + Duration::days(2)),
+        ];
+
+        let total = review_time(&logs);
+        assert_eq!(
+            total,
+            ReviewTime {
+                reviews: 3,
+                total: 21000
+            }
+        );
+        assert_eq!(total.mean(), Some(7000.0));
+
+        let by_day = review_time_by_day(&logs);
+        assert_eq!(by_day.len(), 2);
+        assert_eq!(by_day[&now().date_naive()].total, 16000);
+
+        let by_state = review_time_by_state(&logs);
+        assert_eq!(by_state.get(State::Review).reviews, 2);
+        assert_eq!(by_state.get(State::Learning).mean(), Some(5000.0));
+        assert_eq!(by_state.get(State::New).mean(), None);
+
+        let by_rating = review_time_by_rating(&logs);
+        assert_eq!(by_rating.get(Rating::Good).total, 9000);
+        assert_eq!(by_rating.get(Rating::Again).total, 12000);
+        assert_eq!(by_rating.get(Rating::Easy).reviews, 0);
+    }
+
+    #[test]
+    fn test_card_stats() {
+        let fsrs = FSRS::default();
+        let cards: Vec<Card> = Rating::iter()
+            .map(|&rating| fsrs.next(Card::new_at(now()), now(), rating).card)
+            .chain([Card::new_at(now())])
+            .collect();
+
+        assert_eq!(memorized(&cards, now()), 4.0);
+        let later = now() + Duration::days(30);
+        let expected: f64 = cards
+            .iter()
+            .map(|card| card.get_retrievability(later))
+            .sum();
+        assert_eq!(memorized(&cards, later), expected);
+
+        let stability = stability_distribution(&cards, 1.0).unwrap();
+        assert_eq!(stability.count, 4);
+        assert_eq!(stability.buckets.iter().sum::<usize>(), 4);
+        assert_eq!(stability.max, cards[3].stability);
+        let difficulty = difficulty_distribution(&cards, 1.0).unwrap();
+        assert!(difficulty.min >= 1.0 && difficulty.max <= 10.0);
+        assert_eq!(stability_distribution(&cards[4..], 1.0), None);
+        for width in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert_eq!(stability_distribution(&cards, width), None);
+        }
+        let tiny = stability_distribution(&cards, f64::MIN_POSITIVE).unwrap();
+        assert_eq!(tiny.buckets.len(), MAX_BUCKETS);
+        assert_eq!(tiny.buckets[MAX_BUCKETS - 1], 4);
+
+        assert_eq!(lapse_rate(&cards), Some(0.0));
+        assert_eq!(lapse_rate(&cards[4..]), None);
+
+        let due = due_histogram(&cards, now(), 30);
+        assert_eq!(due.len(), 30);
+        assert_eq!(due[0], 3);
+        assert_eq!(due.iter().sum::<usize>(), 4);
+    }
+}
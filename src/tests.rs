@@ -1,13 +1,21 @@
 #[cfg(test)]
 use {
+    crate::fractional_days::FractionalDays,
     crate::{
-        alea::{AleaState, alea},
+        alea::{AleaState, Prng, alea},
         algo::FSRS,
-        models::{Card, Rating, State},
-        parameters::{Parameters, Seed},
+        explain::StabilityUpdate,
+        fuzz::{Fuzz, NoFuzz},
+        models::{Card, QueueStatus, Rating, ReviewTiming, SchedulingInfo, State},
+        parameters::{FuzzRange, LeechAction, Parameters, ReviewSeed, Seed, SeedPolicy},
+        presets::PresetRegistry,
+        queue::due_cards,
+        record_log::RecordLog,
+        siblings::{bury_siblings, group_siblings},
     },
     chrono::{DateTime, Duration, TimeZone, Utc},
     rand::Rng,
+    std::collections::HashMap,
 };
 
 #[cfg(test)]
@@ -201,6 +209,61 @@ fn test_alea_int32() {
     assert_eq!(generator.int32(), -1369803343);
 }
 
+#[test]
+fn test_alea_quick() {
+    let mut generator = alea(Seed::new("1727015666066"));
+    assert_eq!(generator.quick(), 0.6320083506871015);
+    assert_eq!(generator.quick(), 0.6665704969782382);
+    assert_eq!(generator.quick(), 0.9444492468610406);
+}
+
+#[test]
+fn test_alea_double() {
+    let mut generator = alea(Seed::new(12345));
+    assert_eq!(generator.double(), 0.27138191116884325);
+    assert_eq!(generator.double(), 0.6810678062004586);
+    assert_eq!(generator.double(), 0.3407802057882554);
+
+    let mut generator = alea(Seed::new("Seedp5fxh9kf4r0"));
+    assert_eq!(generator.double(), 0.14867847658465783);
+    assert_eq!(generator.double(), 0.2876111901597712);
+    assert_eq!(generator.double(), 0.2886214772663316);
+}
+
+#[test]
+fn test_alea_int32_seed_string() {
+    let mut generator = alea(Seed::new("Seedp5fxh9kf4r0"));
+    assert_eq!(generator.int32(), 638569194);
+    assert_eq!(generator.int32(), -1931844440);
+    assert_eq!(generator.int32(), 1235280655);
+}
+
+#[test]
+fn test_alea_from_state() {
+    let mut generator = alea(Seed::new(12345));
+    generator.gen_next();
+    let state = generator.get_state();
+    let mut restored = Prng::from_state(state);
+
+    assert_eq!(restored.get_state(), state);
+    for _ in 0..100 {
+        assert_eq!(generator.double(), restored.double());
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_alea_state_serde() {
+    let mut generator = alea(Seed::new("1727015666066"));
+    generator.gen_next();
+    let json = serde_json::to_string(&generator.get_state()).unwrap();
+    let state: AleaState = serde_json::from_str(&json).unwrap();
+    let mut restored = Prng::from_state(state);
+
+    assert_eq!(restored.get_state(), generator.get_state());
+    assert_eq!(restored.gen_next(), generator.gen_next());
+}
+
 #[test]
 fn test_alea_import_state() {
     let mut rng = rand::rng();
@@ -274,6 +337,589 @@ fn test_seed_example_3() {
     assert_eq!(state, expect_alea_state);
 }
 
+#[test]
+fn test_replay() {
+    let fsrs = FSRS::default();
+    let mut card = Card::new();
+    let mut now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let mut logs = vec![];
+
+    for rating in TEST_RATINGS.iter() {
+        let next = fsrs.next(card, now, *rating);
+        card = next.card;
+        logs.push(next.review_log);
+        now = card.due;
+    }
+
+    let replayed = fsrs.replay(Card::new(), &logs);
+    assert_eq!(replayed.stability, card.stability);
+    assert_eq!(replayed.difficulty, card.difficulty);
+    assert_eq!(replayed.due, card.due);
+}
+
+#[test]
+fn test_card_new_at() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card::new_at(now);
+    assert_eq!(card.due, now);
+    assert_eq!(card.last_review, now);
+    assert_eq!(card.state, State::New);
+}
+
+#[test]
+fn test_empty_seed() {
+    assert_eq!(Seed::Empty.inner_str(), "");
+    assert_eq!(Seed::Default.to_string(), "");
+    let mut generator = alea(Seed::Empty);
+    assert!((0.0..1.0).contains(&generator.gen_next()));
+}
+
+#[test]
+fn test_record_log() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let record_log = FSRS::default().repeat(Card::new_at(now), now);
+
+    let ratings: Vec<Rating> = record_log.iter().map(|(rating, _)| rating).collect();
+    assert_eq!(ratings, Rating::iter().copied().collect::<Vec<_>>());
+    for (rating, info) in &record_log {
+        assert_eq!(info.review_log.rating, rating);
+        assert_eq!(record_log[rating].card, info.card);
+        assert_eq!(record_log[&rating].card, info.card);
+        assert_eq!(record_log.get(&rating).unwrap().card, info.card);
+    }
+
+    let map: HashMap<Rating, SchedulingInfo> = record_log.clone().into();
+    assert_eq!(map.len(), 4);
+    let restored = RecordLog::try_from(map.clone()).unwrap();
+    assert_eq!(restored[Rating::Easy].card, record_log[Rating::Easy].card);
+
+    let mut map = map;
+    map.remove(&Rating::Hard);
+    assert_eq!(RecordLog::try_from(map).unwrap_err(), Rating::Hard);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_record_log_serde() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let record_log = FSRS::default().repeat(Card::new_at(now), now);
+
+    let json = serde_json::to_value(&record_log).unwrap();
+    assert_eq!(json["Good"]["review_log"]["rating"], "Good");
+    let restored: RecordLog = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(restored[Rating::Good].card, record_log[Rating::Good].card);
+
+    let mut json = json;
+    json.as_object_mut().unwrap().remove("Again");
+    let error = serde_json::from_value::<RecordLog>(json).unwrap_err();
+    assert!(error.to_string().contains("missing Again"));
+}
+
+#[test]
+fn test_review_duration() {
+    let fsrs = FSRS::default();
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card::new_at(now);
+
+    let timed = fsrs.next_with_duration(card.clone(), now, Rating::Good, 6500);
+    let untimed = fsrs.next(card, now, Rating::Good);
+    assert_eq!(timed.review_log.review_duration, Some(6500));
+    assert_eq!(untimed.review_log.review_duration, None);
+    assert_eq!(timed.card, untimed.card);
+}
+
+#[test]
+fn test_card_ids_in_review_log() {
+    let fsrs = FSRS::default();
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card {
+        id: Some(42),
+        preset_id: Some(7),
+        ..Card::new_at(now)
+    };
+
+    for (_, info) in &fsrs.repeat(card.clone(), now) {
+        assert_eq!((info.card.id, info.card.preset_id), (Some(42), Some(7)));
+        assert_eq!(
+            (info.review_log.card_id, info.review_log.preset_id),
+            (Some(42), Some(7))
+        );
+    }
+    let info = fsrs.next(card, now, Rating::Good);
+    let info = fsrs.next(info.card.clone(), info.card.due, Rating::Good);
+    assert_eq!(
+        (info.review_log.card_id, info.review_log.preset_id),
+        (Some(42), Some(7))
+    );
+}
+
+#[test]
+fn test_retention_override() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    for enable_short_term in [true, false] {
+        let fsrs = FSRS::new(Parameters {
+            enable_short_term,
+            ..Default::default()
+        });
+        let card = review_card(now);
+
+        let default = fsrs.repeat(card.clone(), now);
+        let same = fsrs.repeat_with_retention(card.clone(), now, 0.9);
+        let relaxed = fsrs.repeat_with_retention(card.clone(), now, 0.8);
+        let strict = fsrs.next_with_retention(card.clone(), now, Rating::Good, 0.97);
+        for (rating, info) in &default {
+            assert_eq!(same[rating].card, info.card);
+            assert_eq!(relaxed[rating].card.stability, info.card.stability);
+        }
+        let good = &default[Rating::Good];
+        assert!(relaxed[Rating::Good].card.scheduled_days > good.card.scheduled_days);
+        assert!(strict.card.scheduled_days < good.card.scheduled_days);
+        assert_eq!(
+            fsrs.interval_for_retention(&strict.card, 0.97),
+            strict.interval.unwrap().raw_interval
+        );
+        assert_eq!(fsrs.parameters().request_retention, 0.9);
+    }
+}
+
+#[test]
+fn test_leech_policy() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let lapsed = |lapses| Card {
+        id: Some(5),
+        lapses,
+        ..review_card(now)
+    };
+    for enable_short_term in [true, false] {
+        let mut parameters = Parameters {
+            enable_short_term,
+            ..Default::default()
+        };
+        let fsrs = FSRS::new(parameters.clone());
+
+        let info = fsrs.next(lapsed(7), now, Rating::Again);
+        assert_eq!((info.card.lapses, info.leech), (8, Some(LeechAction::Tag)));
+        assert_eq!(fsrs.next(lapsed(8), now, Rating::Again).leech, None);
+        assert_eq!(
+            fsrs.next(lapsed(11), now, Rating::Again).leech,
+            Some(LeechAction::Tag)
+        );
+        assert_eq!(fsrs.next(lapsed(7), now, Rating::Good).leech, None);
+        let record_log = fsrs.repeat(lapsed(7), now);
+        assert_eq!(record_log[Rating::Again].leech, Some(LeechAction::Tag));
+        assert_eq!(record_log[Rating::Hard].leech, None);
+
+        parameters.leech_action = LeechAction::Suspend;
+        let info = FSRS::new(parameters.clone()).next(lapsed(7), now, Rating::Again);
+        assert_eq!(info.leech, Some(LeechAction::Suspend));
+        assert_eq!(info.card.status, QueueStatus::Suspended);
+
+        parameters.leech_action = LeechAction::Reset;
+        let info = FSRS::new(parameters.clone()).next(lapsed(7), now, Rating::Again);
+        assert_eq!(info.leech, Some(LeechAction::Reset));
+        assert_eq!(
+            info.card,
+            Card {
+                id: Some(5),
+                ..Card::new_at(now)
+            }
+        );
+        assert_eq!(info.review_log.rating, Rating::Again);
+
+        parameters.leech_threshold = 0;
+        let info = FSRS::new(parameters).next(lapsed(7), now, Rating::Again);
+        assert_eq!((info.card.lapses, info.leech), (8, None));
+    }
+}
+
+#[test]
+fn test_review_timing() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::default();
+    let timing = |at| {
+        fsrs.next(review_card(now), at, Rating::Good)
+            .review_log
+            .timing
+    };
+
+    // review_card is due after 20 days, so 15 to 25 days are on time
+    assert_eq!(timing(now - Duration::days(6)), ReviewTiming::Early);
+    assert_eq!(timing(now - Duration::days(5)), ReviewTiming::OnTime);
+    assert_eq!(timing(now + Duration::hours(5)), ReviewTiming::OnTime);
+    assert_eq!(timing(now + Duration::days(5)), ReviewTiming::OnTime);
+    assert_eq!(timing(now + Duration::days(6)), ReviewTiming::Late);
+    let new = fsrs.next(Card::new_at(now), now, Rating::Good);
+    assert_eq!(new.review_log.timing, ReviewTiming::OnTime);
+
+    // learning cards are scheduled 0 days ahead, a day late is still on time
+    assert_eq!(ReviewTiming::new(1, 0), ReviewTiming::OnTime);
+    assert_eq!(ReviewTiming::new(2, 0), ReviewTiming::Late);
+    assert_eq!(ReviewTiming::new(0, 2), ReviewTiming::Early);
+}
+
+#[test]
+fn test_overdue_cap() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = review_card(now);
+    for enable_short_term in [true, false] {
+        let uncapped = FSRS::new(Parameters {
+            enable_short_term,
+            ..Default::default()
+        });
+        let capped = FSRS::new(Parameters {
+            overdue_cap: Some(2.0),
+            ..uncapped.parameters().clone()
+        });
+
+        let late = now + Duration::days(100);
+        let at_cap = card.last_review + Duration::days(40);
+        for rating in Rating::iter() {
+            let expected = uncapped.next(card.clone(), at_cap, *rating).card;
+            let actual = capped.next(card.clone(), late, *rating).card;
+            assert_eq!(actual.stability, expected.stability);
+            assert_eq!(actual.difficulty, expected.difficulty);
+        }
+        assert_eq!(
+            capped
+                .explain(card.clone(), late, Rating::Good)
+                .retrievability,
+            card.get_retrievability(at_cap)
+        );
+        assert_eq!(
+            capped.next(card.clone(), at_cap, Rating::Good).card,
+            uncapped.next(card.clone(), at_cap, Rating::Good).card
+        );
+    }
+}
+
+#[test]
+fn test_overdue_cap_keeps_fuzz_elapsed_days() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::new(Parameters {
+        enable_fuzz: true,
+        overdue_cap: Some(2.0),
+        ..Default::default()
+    });
+    // 100 days elapsed, capped at 40 for the retrievability only
+    let late = now + Duration::days(80);
+    let mut differs = false;
+    for stability in 1..=200 {
+        let card = Card {
+            stability: stability as f64,
+            ..review_card(now)
+        };
+        for rating in [Rating::Hard, Rating::Good, Rating::Easy] {
+            let info = fsrs.next(card.clone(), late, rating).interval.unwrap();
+            let range = (info.min_interval, info.max_interval);
+            let parameters = fsrs.parameters();
+            assert_eq!(range, parameters.get_fuzz_range(info.raw_interval, 100));
+            differs |= range != parameters.get_fuzz_range(info.raw_interval, 40);
+        }
+    }
+    assert!(differs);
+}
+
+#[test]
+fn test_damp_early_reviews() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card {
+        last_review: now - Duration::hours(3),
+        ..review_card(now)
+    };
+    for enable_short_term in [true, false] {
+        let parameters = Parameters {
+            enable_short_term,
+            early_review_damping: Some(0.1542),
+            ..Default::default()
+        };
+        let damped = FSRS::new(parameters.clone());
+        let undamped = FSRS::new(Parameters {
+            early_review_damping: None,
+            ..parameters.clone()
+        });
+
+        for rating in Rating::iter() {
+            let info = damped.next(card.clone(), now, *rating);
+            assert_eq!(
+                info.card.stability,
+                parameters.same_day_stability(card.stability, *rating, 0.1542)
+            );
+            let explanation = damped.explain(card.clone(), now, *rating);
+            assert_eq!(explanation.stability_update, StabilityUpdate::SameDay);
+        }
+        let good = damped.next(card.clone(), now, Rating::Good).card;
+        assert_eq!(good.stability, card.stability);
+        let again = damped.next(card.clone(), now, Rating::Again).card;
+        assert!(again.stability < card.stability);
+        assert!(
+            parameters.same_day_stability(card.stability, Rating::Easy, 0.1542)
+                < parameters.short_term_stability(card.stability, Rating::Easy)
+        );
+
+        let later = now + Duration::days(1);
+        assert_eq!(
+            damped.next(card.clone(), later, Rating::Good).card,
+            undamped.next(card.clone(), later, Rating::Good).card
+        );
+    }
+}
+
+#[test]
+fn test_preset_registry() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let mut presets = PresetRegistry::new(Parameters::default());
+    presets.insert(
+        1,
+        Parameters {
+            request_retention: 0.8,
+            ..Default::default()
+        },
+    );
+    assert!(presets.contains(1) && !presets.contains(2));
+    assert_eq!(presets.get(1).parameters().request_retention, 0.8);
+    assert_eq!(presets.get(2).parameters().request_retention, 0.9);
+    assert_eq!(presets.iter().map(|(id, _)| id).collect::<Vec<_>>(), [1]);
+
+    let card = Card {
+        id: Some(3),
+        ..Card::new_at(now)
+    };
+    let mut logs = vec![];
+    let mut default_card = card.clone();
+    for rating in TEST_RATINGS.iter() {
+        let due = default_card.due;
+        let info = presets.next(default_card, due, *rating);
+        logs.push(info.review_log);
+        default_card = info.card;
+    }
+
+    let migrated = presets.migrate(default_card.clone(), Some(1), &logs);
+    let replayed = presets.get(1).replay(
+        Card {
+            preset_id: Some(1),
+            ..card
+        },
+        &logs,
+    );
+    assert_eq!((migrated.id, migrated.preset_id), (Some(3), Some(1)));
+    assert_eq!(migrated, replayed);
+    assert_eq!(migrated.stability, default_card.stability);
+    assert!(migrated.scheduled_days > default_card.scheduled_days);
+
+    let due = migrated.due;
+    let info = presets.next(migrated.clone(), due, Rating::Good);
+    assert_eq!(
+        info.card,
+        presets.get(1).next(migrated, due, Rating::Good).card
+    );
+
+    let suspended = Card {
+        status: QueueStatus::Suspended,
+        ..default_card
+    };
+    let migrated_suspended = presets.migrate(suspended, Some(1), &logs);
+    assert_eq!(migrated_suspended.status, QueueStatus::Suspended);
+    assert_eq!(
+        Card {
+            status: QueueStatus::Active,
+            ..migrated_suspended
+        },
+        replayed
+    );
+    let buried = Card {
+        status: QueueStatus::Buried(now),
+        ..default_card
+    };
+    assert_eq!(
+        presets.migrate(buried, None, &logs).status,
+        QueueStatus::Buried(now)
+    );
+
+    let unreviewed = presets.migrate(Card::new_at(now), Some(2), []);
+    assert_eq!(
+        unreviewed,
+        Card {
+            preset_id: Some(2),
+            ..Card::new_at(now)
+        }
+    );
+
+    assert!(presets.remove(1).is_some());
+    assert_eq!(presets.get(1).parameters().request_retention, 0.9);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_review_duration_serde() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let info = FSRS::default().next_with_duration(Card::new_at(now), now, Rating::Good, 6500);
+
+    let mut json = serde_json::to_value(&info.review_log).unwrap();
+    assert_eq!(json["review_duration"], 6500);
+    json.as_object_mut().unwrap().remove("review_duration");
+    let restored: crate::ReviewLog = serde_json::from_value(json).unwrap();
+    assert_eq!(restored.review_duration, None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_queue_status_serde() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card {
+        status: QueueStatus::Buried(now),
+        ..Card::new_at(now)
+    };
+
+    let mut json = serde_json::to_value(&card).unwrap();
+    assert_eq!(json["status"]["Buried"], "2022-11-29T12:30:00Z");
+    assert_eq!(serde_json::from_value::<Card>(json.clone()).unwrap(), card);
+    json.as_object_mut().unwrap().remove("status");
+    let restored: Card = serde_json::from_value(json).unwrap();
+    assert_eq!(restored.status, QueueStatus::Active);
+}
+
+#[cfg(test)]
+fn batch_cards(now: DateTime<Utc>) -> Vec<Card> {
+    let fsrs = FSRS::default();
+    (0..2500)
+        .map(|i| {
+            let card = Card::new_at(now - Duration::days(i % 40));
+            let rating = *Rating::iter().nth(i as usize % 4).unwrap();
+            fsrs.next(card, now - Duration::days(i % 40), rating).card
+        })
+        .collect()
+}
+
+#[test]
+fn test_batch() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    });
+    let cards = batch_cards(now);
+    let ratings: Vec<Rating> = (0..cards.len())
+        .map(|i| *Rating::iter().nth(i * 7 % 4).unwrap())
+        .collect();
+
+    let retrievability = fsrs.retrievability_batch(&cards, now);
+    let next = fsrs.next_batch(&cards, now, &ratings);
+    let repeat = fsrs.repeat_batch(&cards, now);
+    assert_eq!(next.len(), cards.len());
+    assert_eq!(repeat.len(), cards.len());
+
+    for (i, card) in cards.iter().enumerate() {
+        assert_eq!(retrievability[i], card.get_retrievability(now));
+        assert_eq!(next[i].card, fsrs.next(card.clone(), now, ratings[i]).card);
+        let record_log = fsrs.repeat(card.clone(), now);
+        for (rating, info) in &repeat[i] {
+            assert_eq!(info.card, record_log[rating].card);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "one rating per card")]
+fn test_next_batch_length_mismatch() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    FSRS::default().next_batch(&[Card::new_at(now)], now, &[]);
+}
+
+#[test]
+fn test_retrievability_projection() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::default();
+    let card = fsrs.next(Card::new_at(now), now, Rating::Easy).card;
+
+    assert_eq!(fsrs.retrievability_at(&Card::new_at(now), now), 0.0);
+    assert_eq!(fsrs.retrievability_at(&card, now), 1.0);
+    assert_eq!(
+        fsrs.retrievability_at(&card, card.due),
+        card.get_retrievability(card.due)
+    );
+
+    // Stability is the time until retrievability drops to 90%.
+    let date = fsrs.retrievability_date(&card, 0.9).unwrap();
+    assert_eq!(date, now + Duration::fractional_days(card.stability));
+    let date = fsrs.retrievability_date(&card, 0.8).unwrap();
+    assert!((fsrs.retrievability_at(&card, date) - 0.8).abs() < 1e-6);
+    assert_eq!(fsrs.retrievability_date(&card, 1.5), None);
+    assert_eq!(fsrs.retrievability_date(&Card::new_at(now), 0.9), None);
+    // dates too far away to represent
+    assert_eq!(fsrs.retrievability_date(&card, 1e-6), None);
+    assert_eq!(fsrs.retrievability_date(&card, f64::MIN_POSITIVE), None);
+
+    let curve = fsrs.retrievability_curve(&card, now, now + Duration::days(30), Duration::days(1));
+    assert_eq!(curve.len(), 31);
+    assert!(curve.windows(2).all(|pair| pair[1].1 < pair[0].1));
+    assert!(
+        fsrs.retrievability_curve(&card, now, now, Duration::zero())
+            .is_empty()
+    );
+    let latest = DateTime::<Utc>::MAX_UTC;
+    let curve =
+        fsrs.retrievability_curve(&card, latest - Duration::days(2), latest, Duration::days(1));
+    assert_eq!(curve.len(), 3);
+
+    let parameters = Parameters::default();
+    assert_eq!(
+        fsrs.interval_for_retention(&card, 0.9),
+        parameters.next_interval(card.stability, 0)
+    );
+    assert!(fsrs.interval_for_retention(&card, 0.8) > fsrs.interval_for_retention(&card, 0.95));
+}
+
+#[test]
+fn test_projection_uses_instance_decay() {
+    let parameters = Parameters {
+        decay: -0.2,
+        factor: 0.9f64.powi(-5) - 1.0,
+        ..Default::default()
+    };
+    assert!((parameters.retrievability(10.0, 10.0) - 0.9).abs() < 1e-12);
+    assert!(parameters.retrievability(20.0, 10.0) > Parameters::forgetting_curve(20.0, 10.0));
+    assert!((parameters.elapsed_days_for_retrievability(10.0, 0.9) - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_scheduler_uses_instance_decay() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let parameters = Parameters {
+        request_retention: 0.8,
+        decay: -0.2,
+        factor: 0.9f64.powi(-5) - 1.0,
+        ..Default::default()
+    };
+    let fsrs = FSRS::new(parameters.clone());
+    let card = Card {
+        stability: 10.0,
+        ..review_card(now)
+    };
+
+    let info = fsrs.next(card.clone(), now, Rating::Good);
+    assert_eq!(
+        info.card.scheduled_days as f64,
+        fsrs.interval_for_retention(&info.card, 0.8)
+    );
+    assert_ne!(
+        info.card.scheduled_days,
+        FSRS::new(Parameters {
+            request_retention: 0.8,
+            ..Default::default()
+        })
+        .next(card.clone(), now, Rating::Good)
+        .card
+        .scheduled_days
+    );
+
+    let expected = parameters.retrievability(20.0, card.stability);
+    assert_eq!(
+        fsrs.explain(card.clone(), now, Rating::Good).retrievability,
+        expected
+    );
+    assert_eq!(fsrs.retrievability_batch(&[card], now), [expected]);
+}
+
 #[test]
 fn test_get_retrievability() {
     let fsrs = FSRS::default();
@@ -289,3 +935,496 @@ fn test_get_retrievability() {
         assert_eq!(retrievability.round_float(7), expect_retrievability[i]);
     }
 }
+
+#[cfg(test)]
+fn review_card(now: DateTime<Utc>) -> Card {
+    Card {
+        due: now,
+        stability: 20.0,
+        difficulty: 5.0,
+        elapsed_days: 20,
+        scheduled_days: 20,
+        reps: 5,
+        lapses: 0,
+        state: State::Review,
+        last_review: now - Duration::days(20),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_next_with_siblings() {
+    let fsrs = FSRS::default();
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = review_card(now);
+    let scheduled_days = fsrs
+        .next(card.clone(), now, Rating::Good)
+        .card
+        .scheduled_days;
+
+    let sibling = Card {
+        due: now + Duration::days(scheduled_days),
+        ..review_card(now)
+    };
+    let spaced = fsrs.next_with_siblings(
+        card.clone(),
+        now,
+        Rating::Good,
+        std::slice::from_ref(&sibling),
+    );
+    assert_ne!(spaced.card.scheduled_days, scheduled_days);
+    assert_eq!((spaced.card.scheduled_days - scheduled_days).abs(), 1);
+    assert_eq!(
+        spaced.card.due,
+        now + Duration::days(spaced.card.scheduled_days)
+    );
+
+    let record_log = fsrs.repeat_with_siblings(card.clone(), now, &[sibling]);
+    assert_eq!(record_log[&Rating::Good].card, spaced.card);
+
+    let unrelated = Card {
+        due: now + Duration::days(scheduled_days + 10),
+        ..review_card(now)
+    };
+    let info = fsrs.next_with_siblings(card, now, Rating::Good, &[unrelated]);
+    assert_eq!(info.card.scheduled_days, scheduled_days);
+}
+
+#[test]
+fn test_siblings_keep_ratings_in_order() {
+    let fsrs = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    });
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    for (stability, elapsed_days) in
+        (1..400).flat_map(|stability| [(stability, 0), (stability, 20)])
+    {
+        let card = Card {
+            stability: stability as f64,
+            elapsed_days,
+            scheduled_days: elapsed_days,
+            last_review: now - Duration::days(elapsed_days),
+            ..review_card(now)
+        };
+        let record_log = fsrs.repeat(card.clone(), now);
+        let sibling = Card {
+            due: record_log[Rating::Good].card.due,
+            ..review_card(now)
+        };
+        let spaced = fsrs.repeat_with_siblings(card, now, &[sibling]);
+        let days = |rating: Rating| spaced[rating].card.scheduled_days;
+        assert!(
+            days(Rating::Hard) < days(Rating::Good),
+            "stability {stability}"
+        );
+        assert!(
+            days(Rating::Good) < days(Rating::Easy),
+            "stability {stability}"
+        );
+    }
+}
+
+#[test]
+fn test_bury_siblings() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let tomorrow = string_to_utc("2022-11-30 00:00:00 +0000 UTC");
+    let later = now + Duration::days(3);
+    let mut siblings = [
+        review_card(now - Duration::days(1)),
+        review_card(now + Duration::hours(2)),
+        Card {
+            due: later,
+            ..review_card(now)
+        },
+        Card {
+            status: QueueStatus::Suspended,
+            ..review_card(now)
+        },
+        Card {
+            status: QueueStatus::Buried(later),
+            ..review_card(now)
+        },
+    ];
+    let dues: Vec<_> = siblings.iter().map(|card| card.due).collect();
+    bury_siblings(&mut siblings, now);
+
+    let statuses: Vec<_> = siblings.iter().map(|card| card.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            QueueStatus::Buried(tomorrow),
+            QueueStatus::Buried(tomorrow),
+            QueueStatus::Active,
+            QueueStatus::Suspended,
+            QueueStatus::Buried(later),
+        ]
+    );
+    assert!(siblings.iter().map(|card| card.due).eq(dues));
+}
+
+#[test]
+fn test_queue_status() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let tomorrow = now + Duration::days(1);
+    let cards = [
+        review_card(now),
+        review_card(tomorrow),
+        Card {
+            status: QueueStatus::Suspended,
+            ..review_card(now)
+        },
+        Card {
+            status: QueueStatus::Buried(tomorrow),
+            ..review_card(now)
+        },
+    ];
+
+    assert_eq!(due_cards(&cards, now).count(), 1);
+    assert_eq!(due_cards(&cards, tomorrow).count(), 3);
+    assert!(!cards[2].is_due(tomorrow) && cards[3].is_due(tomorrow));
+
+    let fsrs = FSRS::default();
+    for card in &cards[2..] {
+        let info = fsrs.next(card.clone(), now, Rating::Good);
+        assert_eq!(info.card.status, card.status);
+    }
+}
+
+#[test]
+fn test_group_siblings() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let groups = group_siblings([
+        (1, review_card(now)),
+        (2, review_card(now)),
+        (1, Card::new()),
+    ]);
+
+    assert_eq!(groups[&1].len(), 2);
+    assert_eq!(groups[&2].len(), 1);
+}
+
+#[test]
+fn test_get_fuzz_range() {
+    assert_eq!(FuzzRange::get_fuzz_range(10.0, 0, 36500), (8, 12));
+    assert_eq!(FuzzRange::get_fuzz_range(10.0, 9, 36500), (10, 12));
+    assert_eq!(FuzzRange::get_fuzz_range(100.0, 0, 100), (93, 100));
+}
+
+#[test]
+fn test_scheduling_info_interval() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = review_card(now);
+    let raw = FSRS::default().next(card.clone(), now, Rating::Good);
+    let fuzzed = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    })
+    .next(card, now, Rating::Good);
+
+    let raw_interval = raw.interval.unwrap();
+    assert_eq!(raw_interval.fuzz_factor, None);
+    assert_eq!(raw_interval.interval, raw_interval.raw_interval);
+    assert_eq!(raw.card.scheduled_days, raw_interval.raw_interval as i64);
+
+    let fuzzed_interval = fuzzed.interval.unwrap();
+    assert_eq!(fuzzed_interval.raw_interval, raw_interval.raw_interval);
+    assert_eq!(
+        (fuzzed_interval.min_interval, fuzzed_interval.max_interval),
+        (raw_interval.min_interval, raw_interval.max_interval)
+    );
+    assert!((0.0..1.0).contains(&fuzzed_interval.fuzz_factor.unwrap()));
+    assert!(
+        (fuzzed_interval.min_interval..=fuzzed_interval.max_interval)
+            .contains(&fuzzed.card.scheduled_days)
+    );
+
+    let learning = FSRS::default().next(Card::new(), now, Rating::Good);
+    assert!(learning.interval.is_none());
+}
+
+#[cfg(test)]
+#[derive(Debug)]
+struct MinimumFuzz;
+
+#[cfg(test)]
+impl Fuzz for MinimumFuzz {
+    fn fuzz_factor(&self, _interval: f64, _min: i64, _max: i64, _seed: &ReviewSeed) -> f64 {
+        0.0
+    }
+}
+
+#[test]
+fn test_custom_fuzz() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = review_card(now);
+    let params = Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    };
+    let expected = FSRS::default().next(card.clone(), now, Rating::Good);
+
+    let no_fuzz = FSRS::new(params.clone()).with_fuzz(NoFuzz);
+    let info = no_fuzz.next(card.clone(), now, Rating::Good);
+    assert_eq!(info.card, expected.card);
+
+    let minimum_fuzz = FSRS::new(params).with_fuzz(MinimumFuzz);
+    let info = minimum_fuzz.next(card, now, Rating::Good);
+    let interval = info.interval.unwrap();
+    assert_eq!(interval.fuzz_factor, Some(0.0));
+    assert_eq!(info.card.scheduled_days, interval.min_interval);
+}
+
+#[test]
+fn test_fsrs_is_unwind_safe() {
+    fn unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>(_: &T) {}
+    let fsrs = FSRS::default().with_fuzz(MinimumFuzz);
+    unwind_safe(&fsrs);
+    unwind_safe(fsrs.parameters());
+    assert!(
+        std::panic::catch_unwind(|| fsrs.next(Card::default(), Utc::now(), Rating::Good)).is_ok()
+    );
+}
+
+#[test]
+fn test_interval_info_is_scheduled_interval() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    // Good's fuzzed interval lands on Hard's, so ordering pushes it a day later
+    let review = Card {
+        stability: 29.0,
+        elapsed_days: 1,
+        scheduled_days: 1,
+        last_review: now - Duration::days(1),
+        ..review_card(now)
+    };
+    let learning = Card {
+        stability: 3.0,
+        state: State::Learning,
+        last_review: now - Duration::days(1),
+        ..review_card(now)
+    };
+    for enable_short_term in [true, false] {
+        let fsrs = FSRS::new(Parameters {
+            enable_fuzz: true,
+            enable_short_term,
+            ..Default::default()
+        })
+        .with_fuzz(MinimumFuzz);
+        for card in [&review, &learning] {
+            let record_log = fsrs.repeat(card.clone(), now);
+            let siblings: Vec<_> = record_log
+                .iter()
+                .map(|(_, info)| Card {
+                    due: info.card.due,
+                    ..review_card(now)
+                })
+                .collect();
+            let spaced = fsrs.repeat_with_siblings(card.clone(), now, &siblings);
+            for (rating, info) in record_log.iter().chain(spaced.iter()) {
+                if let Some(interval) = info.interval {
+                    assert_eq!(interval.interval as i64, info.card.scheduled_days);
+                }
+                let next = fsrs.next_with_siblings(card.clone(), now, rating, &siblings);
+                if let Some(interval) = next.interval {
+                    assert_eq!(interval.interval as i64, next.card.scheduled_days);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_seed_policy() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let card = Card {
+        id: Some(1669725000000),
+        ..review_card(now)
+    };
+
+    let fixed = FSRS::new(Parameters {
+        enable_fuzz: true,
+        seed: Seed::new("fixed"),
+        seed_policy: SeedPolicy::Fixed,
+        ..Default::default()
+    });
+    let info = fixed.next(card.clone(), now, Rating::Good);
+    let expected = alea(Seed::new("fixed")).double();
+    assert_eq!(info.interval.unwrap().fuzz_factor, Some(expected));
+
+    let by_id = FSRS::new(Parameters {
+        enable_fuzz: true,
+        seed_policy: SeedPolicy::CardIdAndReps,
+        ..Default::default()
+    });
+    let info = by_id.next(card.clone(), now, Rating::Good);
+    let expected = alea(Seed::new("1669725000000_6")).double();
+    assert_eq!(info.interval.unwrap().fuzz_factor, Some(expected));
+
+    let later = by_id.next(card.clone(), now + Duration::hours(3), Rating::Good);
+    assert_eq!(later.interval.unwrap().fuzz_factor, Some(expected));
+
+    // cards without an id are seeded from the review, as with SeedPolicy::Card
+    let card = Card { id: None, ..card };
+    let info = by_id.next(card.clone(), now, Rating::Good);
+    let by_card = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    })
+    .next(card, now, Rating::Good);
+    assert_eq!(info.interval, by_card.interval);
+}
+
+#[test]
+fn test_seed_policy_ignores_parameters_seed() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = |seed: &str, enable_short_term| {
+        // a low maximum interval often lifts Easy to the day after Good
+        FSRS::new(Parameters {
+            enable_fuzz: true,
+            enable_short_term,
+            maximum_interval: 30,
+            seed: Seed::new(seed),
+            seed_policy: SeedPolicy::CardIdAndReps,
+            ..Default::default()
+        })
+    };
+    for enable_short_term in [true, false] {
+        let (a, b) = (fsrs("a", enable_short_term), fsrs("b", enable_short_term));
+        for id in 0..100 {
+            let review = Card {
+                id: Some(id),
+                stability: (id % 50 + 1) as f64,
+                ..review_card(now)
+            };
+            let learning = Card {
+                state: State::Learning,
+                ..review.clone()
+            };
+            for card in [Card::new_at(now), learning, review] {
+                let (a, b) = (a.repeat(card.clone(), now), b.repeat(card, now));
+                for rating in Rating::iter() {
+                    assert_eq!(a[rating].card, b[rating].card);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_review_seed() {
+    let seed = ReviewSeed::Card {
+        time: 1669725000000,
+        reps: 3,
+        mul: 12.5,
+    };
+    assert_eq!(seed.to_string(), "1669725000000_3_12.5");
+    let seed = ReviewSeed::CardIdAndReps { id: 7, reps: 2 };
+    assert_eq!(seed.to_string(), "7_2");
+    assert_eq!(ReviewSeed::Fixed(&Seed::new("fixed")).to_string(), "fixed");
+
+    let expected = alea(Seed::new("7_2")).get_state();
+    assert_eq!(Prng::new(seed).get_state(), expected);
+}
+
+#[test]
+fn test_share_fsrs_across_threads() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    });
+    let card = review_card(now);
+    let expected = fsrs.next(card.clone(), now, Rating::Good).card;
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| fsrs.next(card.clone(), now, Rating::Good).card))
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    });
+}
+
+#[cfg(feature = "rand_core")]
+#[test]
+fn test_alea_rng_core() {
+    use rand::SeedableRng;
+    use rand_core::RngCore;
+
+    let mut generator = Prng::seed_from_u64(12345);
+    assert_eq!(generator.next_u32(), 1165576433);
+    assert_eq!(generator.next_u32(), 842497570);
+    assert_eq!(generator.next_u32(), -1369803343i32 as u32);
+
+    let mut seed = [0u8; 32];
+    seed[..5].copy_from_slice(b"12345");
+    let mut a = Prng::from_seed(seed);
+    let mut b = Prng::from_seed(seed);
+    let rolls: Vec<u8> = (0..100).map(|_| a.random_range(1..=6)).collect();
+    assert!(rolls.iter().all(|roll| (1..=6).contains(roll)));
+    assert_eq!(
+        rolls,
+        (0..100).map(|_| b.random_range(1..=6)).collect::<Vec<u8>>()
+    );
+}
+
+#[test]
+fn test_explain_review() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::new(Parameters {
+        enable_fuzz: true,
+        ..Default::default()
+    });
+    let card = review_card(now);
+    let explanation = fsrs.explain(card.clone(), now, Rating::Good);
+    let info = fsrs.next(card.clone(), now, Rating::Good);
+
+    assert_eq!(explanation.elapsed_days, 20);
+    assert_eq!(explanation.retrievability, card.get_retrievability(now));
+    assert_eq!(explanation.next_stability, info.card.stability);
+    assert_eq!(explanation.next_difficulty, info.card.difficulty);
+    let StabilityUpdate::Recall(terms) = explanation.stability_update else {
+        panic!("expected a recall update");
+    };
+    assert!((info.card.stability / card.stability - terms.growth).abs() < 1e-9);
+    assert_eq!(terms.rating_modifier, 1.0);
+
+    let interval = explanation.interval.unwrap();
+    assert_eq!(
+        explanation.ideal_interval.unwrap().round(),
+        interval.raw_interval
+    );
+    assert!(!explanation.clamped);
+    assert!(interval.fuzz_factor.is_some());
+    assert_eq!(explanation.scheduled_days, info.card.scheduled_days);
+    assert_eq!(explanation.due, info.card.due);
+    assert!(explanation.to_string().contains("fuzz range"));
+}
+
+#[test]
+fn test_explain_clamp_and_updates() {
+    let now = string_to_utc("2022-11-29 12:30:00 +0000 UTC");
+    let fsrs = FSRS::new(Parameters {
+        maximum_interval: 10,
+        ..Default::default()
+    });
+    let card = review_card(now);
+    let explanation = fsrs.explain(card.clone(), now, Rating::Easy);
+    assert!(explanation.clamped);
+    assert_eq!(explanation.interval.unwrap().raw_interval, 10.0);
+
+    let again = fsrs.explain(card, now, Rating::Again);
+    assert_eq!(again.stability_update, StabilityUpdate::Forget);
+    assert_eq!(again.interval, None);
+    assert_eq!(again.next_state, State::Relearning);
+
+    let new = fsrs.explain(Card::new_at(now), now, Rating::Good);
+    assert_eq!(new.stability_update, StabilityUpdate::Initial);
+    let learning = fsrs.explain(
+        fsrs.next(Card::new_at(now), now, Rating::Good).card,
+        now,
+        Rating::Hard,
+    );
+    assert_eq!(learning.stability_update, StabilityUpdate::ShortTerm);
+}
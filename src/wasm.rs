@@ -0,0 +1,451 @@
+//! JavaScript bindings built with `wasm-bindgen`.
+//!
+//! Dates cross the boundary as JS `Date` objects, day counts as numbers and a
+//! record log as a plain object keyed by [`Rating`], like `ts-fsrs`.
+
+use chrono::{DateTime, Utc};
+use js_sys::{Date, Float64Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    Card, FSRS, Parameters, QueueStatus, Rating, ReviewLog, ReviewTiming, SchedulingInfo, State,
+};
+
+fn to_datetime(date: &Date) -> Result<DateTime<Utc>, JsError> {
+    let time = date.get_time();
+    if time.is_nan() {
+        return Err(JsError::new("invalid date"));
+    }
+    DateTime::from_timestamp_millis(time as i64).ok_or_else(|| JsError::new("invalid date"))
+}
+
+fn to_date(datetime: DateTime<Utc>) -> Date {
+    Date::new(&JsValue::from_f64(datetime.timestamp_millis() as f64))
+}
+
+#[wasm_bindgen(js_name = Parameters)]
+#[derive(Debug, Clone, Default)]
+pub struct WasmParameters {
+    inner: Parameters,
+}
+
+#[wasm_bindgen(js_class = Parameters)]
+impl WasmParameters {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[wasm_bindgen(getter = requestRetention)]
+    pub fn request_retention(&self) -> f64 {
+        self.inner.request_retention
+    }
+
+    #[wasm_bindgen(setter = requestRetention)]
+    pub fn set_request_retention(&mut self, request_retention: f64) {
+        self.inner.request_retention = request_retention;
+    }
+
+    #[wasm_bindgen(getter = maximumInterval)]
+    pub fn maximum_interval(&self) -> i32 {
+        self.inner.maximum_interval
+    }
+
+    #[wasm_bindgen(setter = maximumInterval)]
+    pub fn set_maximum_interval(&mut self, maximum_interval: i32) {
+        self.inner.maximum_interval = maximum_interval;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn w(&self) -> Float64Array {
+        Float64Array::from(&self.inner.w[..])
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_w(&mut self, w: &Float64Array) -> Result<(), JsError> {
+        if w.length() as usize != self.inner.w.len() {
+            return Err(JsError::new("w must contain 19 weights"));
+        }
+        w.copy_to(&mut self.inner.w);
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter = enableShortTerm)]
+    pub fn enable_short_term(&self) -> bool {
+        self.inner.enable_short_term
+    }
+
+    #[wasm_bindgen(setter = enableShortTerm)]
+    pub fn set_enable_short_term(&mut self, enable_short_term: bool) {
+        self.inner.enable_short_term = enable_short_term;
+    }
+
+    #[wasm_bindgen(getter = enableFuzz)]
+    pub fn enable_fuzz(&self) -> bool {
+        self.inner.enable_fuzz
+    }
+
+    #[wasm_bindgen(setter = enableFuzz)]
+    pub fn set_enable_fuzz(&mut self, enable_fuzz: bool) {
+        self.inner.enable_fuzz = enable_fuzz;
+    }
+}
+
+#[wasm_bindgen(js_name = Card)]
+#[derive(Debug, Clone)]
+pub struct WasmCard {
+    inner: Card,
+}
+
+#[wasm_bindgen(js_class = Card)]
+impl WasmCard {
+    /// Creates a new card due at `now`, or at the current time.
+    #[wasm_bindgen(constructor)]
+    pub fn new(now: Option<Date>) -> Result<Self, JsError> {
+        let now = match now {
+            Some(now) => to_datetime(&now)?,
+            None => Utc::now(),
+        };
+        Ok(Self {
+            inner: Card {
+                due: now,
+                last_review: now,
+                ..Default::default()
+            },
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn id(&self) -> Option<f64> {
+        self.inner.id.map(|id| id as f64)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_id(&mut self, id: Option<f64>) {
+        self.inner.id = id.map(|id| id as i64);
+    }
+
+    #[wasm_bindgen(getter = presetId)]
+    pub fn preset_id(&self) -> Option<f64> {
+        self.inner.preset_id.map(|preset_id| preset_id as f64)
+    }
+
+    #[wasm_bindgen(setter = presetId)]
+    pub fn set_preset_id(&mut self, preset_id: Option<f64>) {
+        self.inner.preset_id = preset_id.map(|preset_id| preset_id as i64);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn due(&self) -> Date {
+        to_date(self.inner.due)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_due(&mut self, due: &Date) -> Result<(), JsError> {
+        self.inner.due = to_datetime(due)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stability(&self) -> f64 {
+        self.inner.stability
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_stability(&mut self, stability: f64) {
+        self.inner.stability = stability;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn difficulty(&self) -> f64 {
+        self.inner.difficulty
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_difficulty(&mut self, difficulty: f64) {
+        self.inner.difficulty = difficulty;
+    }
+
+    #[wasm_bindgen(getter = elapsedDays)]
+    pub fn elapsed_days(&self) -> f64 {
+        self.inner.elapsed_days as f64
+    }
+
+    #[wasm_bindgen(setter = elapsedDays)]
+    pub fn set_elapsed_days(&mut self, elapsed_days: f64) {
+        self.inner.elapsed_days = elapsed_days as i64;
+    }
+
+    #[wasm_bindgen(getter = scheduledDays)]
+    pub fn scheduled_days(&self) -> f64 {
+        self.inner.scheduled_days as f64
+    }
+
+    #[wasm_bindgen(setter = scheduledDays)]
+    pub fn set_scheduled_days(&mut self, scheduled_days: f64) {
+        self.inner.scheduled_days = scheduled_days as i64;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn reps(&self) -> i32 {
+        self.inner.reps
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_reps(&mut self, reps: i32) {
+        self.inner.reps = reps;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn lapses(&self) -> i32 {
+        self.inner.lapses
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_lapses(&mut self, lapses: i32) {
+        self.inner.lapses = lapses;
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> State {
+        self.inner.state
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_state(&mut self, state: State) {
+        self.inner.state = state;
+    }
+
+    #[wasm_bindgen(getter = lastReview)]
+    pub fn last_review(&self) -> Date {
+        to_date(self.inner.last_review)
+    }
+
+    #[wasm_bindgen(setter = lastReview)]
+    pub fn set_last_review(&mut self, last_review: &Date) -> Result<(), JsError> {
+        self.inner.last_review = to_datetime(last_review)?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn suspended(&self) -> bool {
+        self.inner.status == QueueStatus::Suspended
+    }
+
+    /// Suspends the card, or makes a suspended card active again.
+    #[wasm_bindgen(setter)]
+    pub fn set_suspended(&mut self, suspended: bool) {
+        if suspended {
+            self.inner.status = QueueStatus::Suspended;
+        } else if self.inner.status == QueueStatus::Suspended {
+            self.inner.status = QueueStatus::Active;
+        }
+    }
+
+    #[wasm_bindgen(getter = buriedUntil)]
+    pub fn buried_until(&self) -> Option<Date> {
+        match self.inner.status {
+            QueueStatus::Buried(until) => Some(to_date(until)),
+            _ => None,
+        }
+    }
+
+    /// Buries the card until `until`, or unburies it.
+    #[wasm_bindgen(setter = buriedUntil)]
+    pub fn set_buried_until(&mut self, until: Option<Date>) -> Result<(), JsError> {
+        match until {
+            Some(until) => self.inner.status = QueueStatus::Buried(to_datetime(&until)?),
+            None if matches!(self.inner.status, QueueStatus::Buried(_)) => {
+                self.inner.status = QueueStatus::Active;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = isDue)]
+    pub fn is_due(&self, now: &Date) -> Result<bool, JsError> {
+        Ok(self.inner.is_due(to_datetime(now)?))
+    }
+
+    #[wasm_bindgen(js_name = getRetrievability)]
+    pub fn get_retrievability(&self, now: &Date) -> Result<f64, JsError> {
+        Ok(self.inner.get_retrievability(to_datetime(now)?))
+    }
+}
+
+#[wasm_bindgen(js_name = ReviewLog)]
+#[derive(Debug, Clone)]
+pub struct WasmReviewLog {
+    inner: ReviewLog,
+}
+
+#[wasm_bindgen(js_class = ReviewLog)]
+impl WasmReviewLog {
+    #[wasm_bindgen(getter = cardId)]
+    pub fn card_id(&self) -> Option<f64> {
+        self.inner.card_id.map(|card_id| card_id as f64)
+    }
+
+    #[wasm_bindgen(getter = presetId)]
+    pub fn preset_id(&self) -> Option<f64> {
+        self.inner.preset_id.map(|preset_id| preset_id as f64)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rating(&self) -> Rating {
+        self.inner.rating
+    }
+
+    #[wasm_bindgen(getter = elapsedDays)]
+    pub fn elapsed_days(&self) -> f64 {
+        self.inner.elapsed_days as f64
+    }
+
+    #[wasm_bindgen(getter = scheduledDays)]
+    pub fn scheduled_days(&self) -> f64 {
+        self.inner.scheduled_days as f64
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> State {
+        self.inner.state
+    }
+
+    #[wasm_bindgen(getter = reviewedDate)]
+    pub fn reviewed_date(&self) -> Date {
+        to_date(self.inner.reviewed_date)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn timing(&self) -> ReviewTiming {
+        self.inner.timing
+    }
+
+    /// Milliseconds taken to answer, if recorded.
+    #[wasm_bindgen(getter = reviewDuration)]
+    pub fn review_duration(&self) -> Option<u32> {
+        self.inner.review_duration
+    }
+}
+
+#[wasm_bindgen(js_name = SchedulingInfo)]
+#[derive(Debug, Clone)]
+pub struct WasmSchedulingInfo {
+    inner: SchedulingInfo,
+}
+
+#[wasm_bindgen(js_class = SchedulingInfo)]
+impl WasmSchedulingInfo {
+    #[wasm_bindgen(getter)]
+    pub fn card(&self) -> WasmCard {
+        WasmCard {
+            inner: self.inner.card.clone(),
+        }
+    }
+
+    #[wasm_bindgen(getter = reviewLog)]
+    pub fn review_log(&self) -> WasmReviewLog {
+        WasmReviewLog {
+            inner: self.inner.review_log.clone(),
+        }
+    }
+}
+
+#[wasm_bindgen(js_name = FSRS)]
+#[derive(Debug, Clone, Default)]
+pub struct WasmFSRS {
+    inner: FSRS,
+}
+
+#[wasm_bindgen(js_class = FSRS)]
+impl WasmFSRS {
+    #[wasm_bindgen(constructor)]
+    pub fn new(parameters: Option<WasmParameters>) -> Self {
+        Self {
+            inner: FSRS::new(parameters.unwrap_or_default().inner),
+        }
+    }
+
+    /// Returns an object mapping every `Rating` to its `SchedulingInfo`.
+    pub fn repeat(&self, card: &WasmCard, now: &Date) -> Result<Object, JsError> {
+        let record_log = self.inner.repeat(card.inner.clone(), to_datetime(now)?);
+        let object = Object::new();
+        for (rating, inner) in record_log {
+            let info = WasmSchedulingInfo { inner };
+            Reflect::set(&object, &JsValue::from(rating as u32), &info.into())
+                .map_err(|_| JsError::new("failed to build record log"))?;
+        }
+        Ok(object)
+    }
+
+    pub fn next(
+        &self,
+        card: &WasmCard,
+        now: &Date,
+        rating: Rating,
+        review_duration: Option<u32>,
+    ) -> Result<WasmSchedulingInfo, JsError> {
+        let (card, now) = (card.inner.clone(), to_datetime(now)?);
+        let info = match review_duration {
+            Some(duration) => self.inner.next_with_duration(card, now, rating, duration),
+            None => self.inner.next(card, now, rating),
+        };
+        Ok(WasmSchedulingInfo { inner: info })
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const NOW: f64 = 1669725000000.0;
+
+    #[wasm_bindgen_test]
+    fn test_repeat() {
+        let now = Date::new(&NOW.into());
+        let fsrs = WasmFSRS::new(None);
+        let card = WasmCard::new(Some(now.clone())).unwrap();
+        let record_log = fsrs.repeat(&card, &now).unwrap();
+
+        let good = Reflect::get(&record_log, &JsValue::from(Rating::Good as u32)).unwrap();
+        assert!(good.is_object());
+        assert_eq!(Object::keys(&record_log).length(), 4);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_next() {
+        let now = Date::new(&NOW.into());
+        let fsrs = WasmFSRS::new(Some(WasmParameters::new()));
+        let mut card = WasmCard::new(Some(now.clone())).unwrap();
+        card.set_suspended(true);
+        let info = fsrs.next(&card, &now, Rating::Easy, Some(3000)).unwrap();
+        let mut card = info.card();
+        assert!(card.suspended() && !card.is_due(&card.due()).unwrap());
+        card.set_suspended(false);
+
+        assert_eq!(card.state(), State::Review);
+        assert_eq!(info.review_log().rating(), Rating::Easy);
+        assert_eq!(info.review_log().review_duration(), Some(3000));
+        assert_eq!(info.review_log().timing(), ReviewTiming::OnTime);
+        assert_eq!(
+            card.due().get_time(),
+            NOW + card.scheduled_days() * 86_400_000.0
+        );
+        assert!((card.get_retrievability(&card.due()).unwrap() - 0.9026208).abs() < 1e-7);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_invalid_date() {
+        let invalid = Date::new(&JsValue::from_str("not a date"));
+        assert!(invalid.get_time().is_nan());
+        assert!(WasmCard::new(Some(invalid.clone())).is_err());
+
+        let now = Date::new(&NOW.into());
+        let card = WasmCard::new(Some(now)).unwrap();
+        let fsrs = WasmFSRS::new(None);
+        assert!(fsrs.repeat(&card, &invalid).is_err());
+        assert!(card.get_retrievability(&invalid).is_err());
+    }
+}